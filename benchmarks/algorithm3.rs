@@ -0,0 +1,11 @@
+// The reference solution of algorithm3, which the benchmark of the exercise
+// is timed against
+
+pub fn bench(input: &[u64]) -> u64 {
+    let mut array = input.to_vec();
+    array.sort_unstable();
+    array
+        .iter()
+        .step_by((array.len() / 100).max(1))
+        .fold(0, |sum, v| sum.wrapping_mul(31).wrapping_add(*v))
+}
@@ -0,0 +1,19 @@
+// The reference solution of algorithm4, which the benchmark of the exercise
+// is timed against
+
+use std::collections::BTreeSet;
+
+pub fn bench(input: &[u64]) -> u64 {
+    let mut set = BTreeSet::new();
+    for &value in input {
+        set.insert(value);
+    }
+    let found = input.iter().filter(|&&value| set.contains(&(value / 2))).count() as u64;
+
+    let n = input.len() as u64;
+    let mut sorted = BTreeSet::new();
+    for value in 0..n {
+        sorted.insert(value);
+    }
+    found + (0..n).filter(|&value| sorted.contains(&(value * 2))).count() as u64
+}
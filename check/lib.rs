@@ -0,0 +1,327 @@
+//! The property testing harness rustlings links into the exercises as the
+//! `rustlings_check` crate.
+//!
+//! A property is a function which should hold for any input, like "sorting a
+//! vector gives the same vector as `sort`". `check` calls it with random
+//! inputs, and when one makes it fail, shrinks that input to a smaller one
+//! which still fails it. The inputs come from a seed, printed on failure,
+//! which replays them when it's set in the `RUSTLINGS_SEED` environment
+//! variable. It only uses the standard library, so that rustlings can build
+//! it with `rustc` alone.
+
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable replaying the inputs of a seed
+const SEED_VAR: &str = "RUSTLINGS_SEED";
+/// How many inputs a property is checked with
+const CASES: usize = 100;
+/// The largest size of the inputs, like the length of vectors
+const MAX_SIZE: usize = 100;
+/// How many smaller inputs are tried at most while shrinking
+const MAX_SHRINKS: usize = 1000;
+
+/// A pseudo-random number generator, which gives the same numbers for the
+/// same seed
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// The next number, from splitmix64
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 to `bound`, excluded
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            0
+        } else {
+            self.next_u64() % bound
+        }
+    }
+}
+
+/// The values a property can be checked with
+pub trait Arbitrary: Clone + Debug {
+    /// A random value, `size` bounding how large it is
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self;
+
+    /// Smaller values than this one, the most promising first
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+macro_rules! arbitrary_integers {
+    ($($int:ty),*) => {$(
+        impl Arbitrary for $int {
+            fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+                // Mostly small numbers, which are easier to read, with a few
+                // of any size to reach the edge cases
+                if rng.below(10) == 0 {
+                    rng.next_u64() as $int
+                } else {
+                    let mut small = rng.below(2 * size as u64 + 1) as i128 - size as i128;
+                    if <$int>::MIN == 0 {
+                        small = small.abs();
+                    }
+                    small.clamp(<$int>::MIN as i128, <$int>::MAX as i128) as $int
+                }
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                let value = *self;
+                let mut smaller = Vec::new();
+                if value != 0 {
+                    smaller.push(0);
+                }
+                // Halving, then stepping towards zero
+                let mut step = value / 2;
+                while step != 0 {
+                    let candidate = value - step;
+                    if !smaller.contains(&candidate) {
+                        smaller.push(candidate);
+                    }
+                    step /= 2;
+                }
+                smaller
+            }
+        }
+    )*};
+}
+
+arbitrary_integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng, _size: usize) -> Self {
+        rng.below(2) == 1
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng, _size: usize) -> Self {
+        // Mostly lowercase letters, sometimes anything printable in ASCII
+        if rng.below(4) == 0 {
+            (b' ' + rng.below(95) as u8) as char
+        } else {
+            (b'a' + rng.below(26) as u8) as char
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self == 'a' {
+            Vec::new()
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        let len = rng.below(size as u64 + 1) as usize;
+        (0..len).map(|_| T::arbitrary(rng, size)).collect()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut smaller = Vec::new();
+        if self.is_empty() {
+            return smaller;
+        }
+        // Without whole chunks first, then without single elements, then
+        // with smaller elements
+        let mut chunk = self.len() / 2;
+        while chunk > 0 {
+            for start in (0..self.len()).step_by(chunk) {
+                let mut candidate = self[..start].to_vec();
+                candidate.extend_from_slice(&self[(start + chunk).min(self.len())..]);
+                smaller.push(candidate);
+            }
+            chunk /= 2;
+        }
+        for (i, element) in self.iter().enumerate() {
+            for shrunk in element.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = shrunk;
+                smaller.push(candidate);
+            }
+        }
+        smaller
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        Vec::<char>::arbitrary(rng, size).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        chars
+            .shrink()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+        if rng.below(4) == 0 {
+            None
+        } else {
+            Some(T::arbitrary(rng, size))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            None => Vec::new(),
+            Some(value) => std::iter::once(None)
+                .chain(value.shrink().into_iter().map(Some))
+                .collect(),
+        }
+    }
+}
+
+macro_rules! arbitrary_tuples {
+    ($(($($t:ident $i:tt),*)),*) => {$(
+        impl<$($t: Arbitrary),*> Arbitrary for ($($t,)*) {
+            fn arbitrary(rng: &mut Rng, size: usize) -> Self {
+                ($($t::arbitrary(rng, size),)*)
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                let mut smaller = Vec::new();
+                $(
+                    for shrunk in self.$i.shrink() {
+                        let mut candidate = self.clone();
+                        candidate.$i = shrunk;
+                        smaller.push(candidate);
+                    }
+                )*
+                smaller
+            }
+        }
+    )*};
+}
+
+arbitrary_tuples!((A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+thread_local! {
+    // Whether the panics of the current thread are expected, as the
+    // property is checked
+    static CHECKING: Cell<bool> = Cell::new(false);
+}
+
+// Silence the panics of the properties while they are checked, as a panic
+// only means that the property doesn't hold. Other threads, like the other
+// tests, still report theirs.
+fn silence_panics() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CHECKING.with(Cell::get) {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+// Whether the property holds for the input, which it doesn't if it panics
+fn holds<T: Arbitrary>(property: &impl Fn(T) -> bool, input: &T) -> bool {
+    CHECKING.with(|checking| checking.set(true));
+    let holds = panic::catch_unwind(AssertUnwindSafe(|| property(input.clone())));
+    CHECKING.with(|checking| checking.set(false));
+    holds.unwrap_or(false)
+}
+
+// The smallest input found to still fail the property
+fn shrink<T: Arbitrary>(property: &impl Fn(T) -> bool, mut input: T) -> T {
+    let mut tries = 0;
+    'shrinking: while tries < MAX_SHRINKS {
+        for candidate in input.shrink() {
+            tries += 1;
+            if !holds(property, &candidate) {
+                input = candidate;
+                continue 'shrinking;
+            }
+            if tries >= MAX_SHRINKS {
+                break;
+            }
+        }
+        break;
+    }
+    input
+}
+
+// The seed given in the environment, or a new one
+fn seed() -> u64 {
+    if let Ok(seed) = std::env::var(SEED_VAR) {
+        let parsed = match seed.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => seed.parse(),
+        };
+        return parsed.unwrap_or_else(|_| panic!("{SEED_VAR} isn't a number: {seed}"));
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64);
+    // Tests running at the same time get different seeds
+    let local = 0u8;
+    nanos ^ (&local as *const u8 as u64).rotate_left(32)
+}
+
+/// Checks that `property` holds for random inputs, and panics with the
+/// smallest input it found to fail it otherwise. The property fails by
+/// returning false or by panicking, like with `assert!`.
+///
+/// ```ignore
+/// rustlings_check::check(|mut numbers: Vec<i32>| {
+///     let mut expected = numbers.clone();
+///     expected.sort();
+///     sort(&mut numbers);
+///     numbers == expected
+/// });
+/// ```
+pub fn check<T: Arbitrary>(property: impl Fn(T) -> bool) {
+    silence_panics();
+    let seed = seed();
+    let mut rng = Rng::new(seed);
+    for case in 0..CASES {
+        let size = (case + 1) * MAX_SIZE / CASES;
+        let input = T::arbitrary(&mut rng, size);
+        if !holds(&property, &input) {
+            let smallest = shrink(&property, input.clone());
+            panic!(
+                "the property doesn't hold after {} cases\n\
+                 failing input: {input:?}\n\
+                 smallest failing input: {smallest:?}\n\
+                 rerun with {SEED_VAR}={seed:#x} to get the same inputs",
+                case + 1
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 /*
 	binary_search tree
 	This problem requires you to implement a basic interface for a binary tree
+	The tree has to stay balanced, like an AVL or a red-black tree: once the
+	tests pass, it's timed with numbers inserted in increasing order too
 */
 
 //
@@ -169,3 +171,23 @@ mod tests {
 }    
 
 
+
+// Inserts the numbers in a tree then searches for as many, then does the
+// same in increasing order, for the benchmark rustlings runs once the tests
+// pass
+#[cfg(rustlings_bench)]
+pub fn bench(input: &[u64]) -> u64 {
+    let mut bst = BinarySearchTree::new();
+    for &value in input {
+        bst.insert(value);
+    }
+    let found = input.iter().filter(|&&value| bst.search(value / 2)).count() as u64;
+
+    // Inserted in order, the numbers make an unbalanced tree a list
+    let n = input.len() as u64;
+    let mut sorted = BinarySearchTree::new();
+    for value in 0..n {
+        sorted.insert(value);
+    }
+    found + (0..n).filter(|&value| sorted.search(value * 2)).count() as u64
+}
@@ -114,4 +114,27 @@ mod tests {
         assert_eq!(s.pop(), Err("Stack is empty"));
         assert_eq!(s.is_empty(), true);
 	}
-}
\ No newline at end of file
+	#[test]
+	fn test_stack_like_vec(){
+		// Random pushes (Some) and pops (None) give the same results as
+		// with a Vec
+		rustlings_check::check(|operations: Vec<Option<i32>>| {
+			let mut s = MyStack::new();
+			let mut expected = Vec::new();
+			for operation in operations {
+				match operation {
+					Some(value) => {
+						s.push(value);
+						expected.push(value);
+					}
+					None => {
+						if s.pop().ok() != expected.pop() {
+							return false;
+						}
+					}
+				}
+			}
+			s.is_empty() == expected.is_empty()
+		});
+	}
+}
@@ -0,0 +1,261 @@
+//! The executor the async exercises of rustlings run on.
+//!
+//! It only uses the standard library, so that rustlings can build it with
+//! `rustc` alone and link it into the exercises as the `rustlings_runtime`
+//! crate. Every future given to `block_on` or `spawn` runs on a thread of its
+//! own, which is slow but keeps the executor small enough to read.
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// The environment variable rustlings sets to the timeout of the exercise,
+/// in milliseconds
+const TIMEOUT_VAR: &str = "RUSTLINGS_TIMEOUT_MS";
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread and returns its output.
+///
+/// Panics if the future takes longer than the timeout of the exercise, which
+/// usually means that it waits for something that never happens.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let timeout = std::env::var(TIMEOUT_VAR)
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .map(Duration::from_millis);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        match (deadline, timeout) {
+            (Some(deadline), Some(timeout)) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    panic!(
+                        "the future didn't complete within {timeout:?}, \
+                         is it waiting for something that never happens?"
+                    );
+                }
+                thread::park_timeout(deadline - now);
+            }
+            _ => thread::park(),
+        }
+    }
+}
+
+struct Task<T> {
+    // The output of the future, or the panic it ended with
+    output: Option<thread::Result<T>>,
+    // The waker of the future waiting on the task's `JoinHandle`
+    waker: Option<Waker>,
+}
+
+/// A future resolving to the output of a spawned future.
+///
+/// If the spawned future panicked, awaiting its handle panics too.
+pub struct JoinHandle<T> {
+    task: Arc<Mutex<Task<T>>>,
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut task = self.task.lock().unwrap();
+        match task.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                task.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Starts running a future in the background. It runs whether or not the
+/// returned handle is awaited.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let task = Arc::new(Mutex::new(Task {
+        output: None,
+        waker: None,
+    }));
+    let spawned_task = Arc::clone(&task);
+    thread::spawn(move || {
+        let output = panic::catch_unwind(AssertUnwindSafe(|| block_on(future)));
+        let mut task = spawned_task.lock().unwrap();
+        task.output = Some(output);
+        if let Some(waker) = task.waker.take() {
+            waker.wake();
+        }
+    });
+    JoinHandle { task }
+}
+
+/// A future completing once its duration has passed.
+pub struct Sleep {
+    deadline: Instant,
+    // The waker to call when the duration has passed, shared with the timer
+    // thread once it's started
+    waker: Option<Arc<Mutex<Waker>>>,
+}
+
+/// Waits until `duration` has passed, without blocking the thread.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now() + duration,
+        waker: None,
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return Poll::Ready(());
+        }
+        match &self.waker {
+            Some(waker) => *waker.lock().unwrap() = cx.waker().clone(),
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                let timer_waker = Arc::clone(&waker);
+                let remaining = self.deadline - now;
+                thread::spawn(move || {
+                    thread::sleep(remaining);
+                    timer_waker.lock().unwrap().wake_by_ref();
+                });
+                self.waker = Some(waker);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Lets other futures make progress before continuing.
+pub async fn yield_now() {
+    YieldNow(false).await
+}
+
+struct Join<A: Future, B: Future> {
+    a: Pin<Box<A>>,
+    b: Pin<Box<B>>,
+    a_output: Option<A::Output>,
+    b_output: Option<B::Output>,
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Every field is either boxed or not pinned
+        let join = unsafe { self.get_unchecked_mut() };
+        if join.a_output.is_none() {
+            if let Poll::Ready(output) = join.a.as_mut().poll(cx) {
+                join.a_output = Some(output);
+            }
+        }
+        if join.b_output.is_none() {
+            if let Poll::Ready(output) = join.b.as_mut().poll(cx) {
+                join.b_output = Some(output);
+            }
+        }
+        if join.a_output.is_some() && join.b_output.is_some() {
+            Poll::Ready((join.a_output.take().unwrap(), join.b_output.take().unwrap()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Waits for two futures at the same time, and returns both of their outputs.
+pub async fn join<A: Future, B: Future>(a: A, b: B) -> (A::Output, B::Output) {
+    Join {
+        a: Box::pin(a),
+        b: Box::pin(b),
+        a_output: None,
+        b_output: None,
+    }
+    .await
+}
+
+/// Defines an async `main` function, run with `block_on`:
+///
+/// ```ignore
+/// rustlings_runtime::async_main! {
+///     async fn main() {
+///         println!("{}", greeting().await);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! async_main {
+    (async fn main() $body:block) => {
+        fn main() {
+            $crate::block_on(async $body)
+        }
+    };
+}
+
+/// Defines async tests, each run with `block_on`, so that a test whose future
+/// never completes fails once the timeout has passed:
+///
+/// ```ignore
+/// rustlings_runtime::async_test! {
+///     async fn greets() {
+///         assert_eq!(greeting().await, "Hello!");
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! async_test {
+    ($($(#[$attr:meta])* async fn $name:ident() $body:block)*) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $name() {
+                $crate::block_on(async $body)
+            }
+        )*
+    };
+}
@@ -0,0 +1,272 @@
+use crate::exercise::Exercise;
+use serde::Deserialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Where the benchmarks are built
+const BENCHMARKS_DIR: &str = "target/rustlings/bench";
+// The cfg under which the exercises define their `bench` function
+const BENCH_CFG: &str = "rustlings_bench";
+// How many times a size is run before the exercise is found too slow
+const RUNS: usize = 3;
+// The smallest time budget, below which timings are mostly noise
+const MIN_BUDGET: Duration = Duration::from_millis(1);
+// How long a run may go on past its deadline before it's killed, for the
+// process to start and build the input
+const KILL_SLACK: Duration = Duration::from_millis(500);
+// How many lines of what a crashed run printed are shown
+const CRASH_LINES: usize = 10;
+
+// Whether the benchmark stage is left out when the exercises are checked,
+// for it to be run on its own once nothing else competes for the machine
+static DEFERRED: AtomicBool = AtomicBool::new(false);
+
+// The benchmark stage of an exercise, run once its tests pass. The exercise
+// and its reference solution both define
+// `#[cfg(rustlings_bench)] pub fn bench(input: &[u64]) -> u64`, which does
+// the work for the numbers of `input` and returns a checksum of the result.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Benchmark {
+    // The solution the exercise is timed against
+    pub reference: PathBuf,
+    // The sizes `bench` is called with, from the smallest
+    pub sizes: Vec<usize>,
+    // How many times slower than the reference the exercise may be
+    pub budget: f64,
+}
+
+// The timings of a size
+struct Timing {
+    size: usize,
+    reference: Duration,
+    allowed: Duration,
+    // None if the exercise took longer than allowed
+    exercise: Option<Duration>,
+}
+
+// How a run of a benchmark ended
+enum Run {
+    // `bench` took this long and returned this checksum
+    Finished(Duration, u64),
+    // It took longer than its deadline
+    TooSlow,
+    // The process failed, like with a panic or a stack overflow, and
+    // printed this
+    Crashed(String),
+}
+
+// The driver calling the `bench` function of an exercise and printing how
+// long it took. Its input has as many numbers as the size given as its
+// argument, the same pseudo-random ones below 1 000 000 for every run.
+fn driver(path: &Path) -> String {
+    format!(
+        r#"#![allow(warnings)]
+
+#[path = {path:?}]
+mod exercise;
+
+fn main() {{
+    let size = std::env::args()
+        .nth(1)
+        .and_then(|size| size.parse().ok())
+        .expect("The size to benchmark is missing");
+    let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+    let input: Vec<u64> = (0..size)
+        .map(|_| {{
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x % 1_000_000
+        }})
+        .collect();
+    let start = std::time::Instant::now();
+    let checksum = exercise::bench(&input);
+    let elapsed = start.elapsed();
+    println!("{{}} {{}}", elapsed.as_nanos(), checksum);
+}}
+"#
+    )
+}
+
+// Build the driver of `path` as the executable `name`, optimized
+fn build(exercise: &Exercise, path: &Path, name: &str) -> Result<PathBuf, String> {
+    let dir = Path::new(BENCHMARKS_DIR).join(&exercise.name);
+    let error = |e: std::io::Error| format!("Failed to build the benchmark of {exercise}: {e}");
+    fs::create_dir_all(&dir).map_err(error)?;
+    let path = fs::canonicalize(path).map_err(error)?;
+    let driver_path = dir.join(format!("{name}.rs"));
+    fs::write(&driver_path, driver(&path)).map_err(error)?;
+    let executable = dir.join(name);
+    let output = Command::new("rustc")
+        .arg(&driver_path)
+        .arg("-o")
+        .arg(&executable)
+        .args(["-C", "opt-level=3", "--cfg", BENCH_CFG])
+        .args(exercise.rustc_args())
+        .args(exercise.bundled_crate_args()?)
+        .output()
+        .map_err(error)?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to build the benchmark of {exercise}, does {} define `pub fn bench(input: &[u64]) -> u64`?\n{}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(executable)
+}
+
+// Run `executable` for a size. It's too slow if `bench` took longer than
+// `deadline`, and it's killed if it goes on for much longer.
+fn time(executable: &Path, size: usize, deadline: Option<Duration>) -> Result<Run, String> {
+    let error = |e: std::io::Error| format!("Failed to run {}: {e}", executable.display());
+    let mut child = Command::new(executable)
+        .arg(size.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(error)?;
+    let start = Instant::now();
+    while child.try_wait().map_err(error)?.is_none() {
+        if deadline.is_some_and(|deadline| start.elapsed() > deadline * 2 + KILL_SLACK) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(Run::TooSlow);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    let output = child.wait_with_output().map_err(error)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().take(CRASH_LINES).collect();
+        return Ok(Run::Crashed(lines.join("\n")));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split_whitespace().map(str::parse::<u64>);
+    let (elapsed, checksum) = match (fields.next(), fields.next()) {
+        (Some(Ok(nanos)), Some(Ok(checksum))) => (Duration::from_nanos(nanos), checksum),
+        _ => {
+            return Err(format!(
+                "{} didn't print its timing and checksum",
+                executable.display()
+            ))
+        }
+    };
+    Ok(match deadline {
+        Some(deadline) if elapsed > deadline => Run::TooSlow,
+        _ => Run::Finished(elapsed, checksum),
+    })
+}
+
+// The fastest of a few runs, which is the least disturbed by the machine.
+// A run going over the deadline or crashing ends it.
+fn best_time(executable: &Path, size: usize, deadline: Option<Duration>) -> Result<Run, String> {
+    let mut best = None;
+    for _ in 0..RUNS {
+        match time(executable, size, deadline)? {
+            Run::Finished(elapsed, checksum) => {
+                best = Some(
+                    best.map_or((elapsed, checksum), |(best, _): (Duration, u64)| {
+                        (best.min(elapsed), checksum)
+                    }),
+                );
+            }
+            run => return Ok(run),
+        }
+    }
+    Ok(best.map_or(Run::TooSlow, |(elapsed, checksum)| {
+        Run::Finished(elapsed, checksum)
+    }))
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn report(exercise: &Exercise, benchmark: &Benchmark, timings: &[Timing]) -> String {
+    let mut report = format!(
+        "Benchmark of {exercise}, allowed to take {}x the time of the reference solution:\n",
+        benchmark.budget
+    );
+    let _ = writeln!(
+        report,
+        "{:>10} {:>12} {:>12}",
+        "size", "reference", "solution"
+    );
+    for timing in timings {
+        let exercise = match timing.exercise {
+            Some(elapsed) => format!("{:>12} ✓", format_duration(elapsed)),
+            None => format!("{:>12} ✗", format!("> {}", format_duration(timing.allowed))),
+        };
+        let _ = writeln!(
+            report,
+            "{:>10} {:>12} {exercise}",
+            timing.size,
+            format_duration(timing.reference)
+        );
+    }
+    report
+}
+
+// Leave the benchmark stage out of the checks of the exercises, or put it
+// back in
+pub fn defer(deferred: bool) {
+    DEFERRED.store(deferred, Ordering::Relaxed);
+}
+
+pub fn deferred() -> bool {
+    DEFERRED.load(Ordering::Relaxed)
+}
+
+// Time the exercise against its reference solution for every size, from
+// the smallest, and stop at the first one it's too slow for, crashes with
+// or returns another checksum than the reference for. Ok holds the report
+// of the timings if the exercise is fast enough, Err otherwise.
+pub fn run(exercise: &Exercise, benchmark: &Benchmark) -> Result<String, String> {
+    let reference = build(exercise, &benchmark.reference, "reference")?;
+    let solution = build(exercise, &exercise.path, "solution")?;
+    let mut timings = Vec::new();
+    for &size in &benchmark.sizes {
+        let (reference, expected) = match best_time(&reference, size, None)? {
+            Run::Finished(elapsed, checksum) => (elapsed, checksum),
+            Run::TooSlow | Run::Crashed(_) => {
+                return Err(format!(
+                    "The reference solution {} failed for inputs of size {size}",
+                    benchmark.reference.display()
+                ))
+            }
+        };
+        let allowed = reference.max(MIN_BUDGET).mul_f64(benchmark.budget);
+        let timing = |exercise| Timing {
+            size,
+            reference,
+            allowed,
+            exercise,
+        };
+        match best_time(&solution, size, Some(allowed))? {
+            Run::Finished(elapsed, checksum) => {
+                timings.push(timing(Some(elapsed)));
+                if checksum != expected {
+                    return Err(report(exercise, benchmark, &timings)
+                        + &format!("\n{exercise} returned {checksum} for inputs of size {size}, but the reference solution returned {expected}.\n\
+                                    Its `bench` function has to do the same work as the one of the reference.\n"));
+                }
+            }
+            Run::TooSlow => {
+                timings.push(timing(None));
+                return Err(report(exercise, benchmark, &timings)
+                    + &format!("\n{exercise} is too slow for inputs of size {size}, it needs a faster algorithm.\n"));
+            }
+            Run::Crashed(stderr) => {
+                return Err(report(exercise, benchmark, &timings)
+                    + &format!("\n{exercise} crashed for inputs of size {size}, which a recursion too deep for the stack can do. It printed:\n{stderr}\n"));
+            }
+        }
+    }
+    Ok(report(exercise, benchmark, &timings))
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::{Mutex, OnceLock};
+
+// Where the bundled crates are built
+const BUNDLED_DIR: &str = "target/rustlings/bundled";
+// The environment variable giving the async runtime the timeout of an exercise
+pub const TIMEOUT_VAR: &str = "RUSTLINGS_TIMEOUT_MS";
+// The environment variable setting the seed of the property tests, which
+// they print when they fail
+pub const SEED_VAR: &str = "RUSTLINGS_SEED";
+
+// A crate bundled with rustlings, which the exercises can be linked with.
+// It only uses the standard library, so that it's built with rustc alone.
+pub struct BundledCrate {
+    // The name the exercises use the crate by
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+// The executor of the async exercises, see runtime/lib.rs
+pub static RUNTIME: BundledCrate = BundledCrate {
+    name: "rustlings_runtime",
+    source: include_str!("../runtime/lib.rs"),
+};
+
+// The property testing harness of the exercises, see check/lib.rs
+pub static CHECK: BundledCrate = BundledCrate {
+    name: "rustlings_check",
+    source: include_str!("../check/lib.rs"),
+};
+
+impl BundledCrate {
+    // The directory the crate is written and built in
+    pub fn package_dir(&self) -> PathBuf {
+        Path::new(BUNDLED_DIR).join(self.name)
+    }
+
+    // Where the source of the crate is written for rustc and rust-analyzer
+    pub fn source_path(&self) -> PathBuf {
+        self.package_dir().join("lib.rs")
+    }
+
+    // Where the manifest of the crate is written for the exercises built
+    // with cargo, which depend on it by path
+    pub fn manifest_path(&self) -> PathBuf {
+        self.package_dir().join("Cargo.toml")
+    }
+
+    // The manifest of the crate as a cargo package, next to its source
+    pub fn manifest(&self) -> String {
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.1\"\nedition = \"2021\"\n\n\
+             [lib]\npath = \"lib.rs\"\n",
+            self.name
+        )
+    }
+
+    // Write the source and the manifest of the crate, for the exercises
+    // built with cargo. They are only written when they changed, so that
+    // cargo doesn't rebuild the crate every time.
+    pub fn write_package(&self) -> std::io::Result<()> {
+        for (path, contents) in [
+            (self.source_path(), self.source.to_string()),
+            (self.manifest_path(), self.manifest()),
+        ] {
+            if fs::read_to_string(&path).ok() != Some(contents.clone()) {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&path, contents)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Build the crate with the rustc the exercises are compiled with, and
+    // return the path of the library. It's built once per run of rustlings,
+    // as an older build may come from another version of rustc.
+    pub fn build(&self) -> Result<PathBuf, String> {
+        static LIBRARIES: OnceLock<Mutex<HashMap<&str, Result<PathBuf, String>>>> = OnceLock::new();
+        let mut libraries = LIBRARIES.get_or_init(Default::default).lock().unwrap();
+        libraries
+            .entry(self.name)
+            .or_insert_with(|| self.compile())
+            .clone()
+    }
+
+    fn compile(&self) -> Result<PathBuf, String> {
+        let error = |e: std::io::Error| format!("Failed to build {}: {e}", self.name);
+        let source_path = self.source_path();
+        let dir = source_path.parent().unwrap_or(Path::new(BUNDLED_DIR));
+        fs::create_dir_all(dir).map_err(error)?;
+        if fs::read_to_string(&source_path).ok().as_deref() != Some(self.source) {
+            fs::write(&source_path, self.source).map_err(error)?;
+        }
+        // Other instances of rustlings may be building it at the same time,
+        // so it's built under a name of its own and moved in place
+        let library = dir.join(format!("lib{}.rlib", self.name));
+        let temp_library = library.with_extension(format!("{}.rlib", process::id()));
+        let output = Command::new("rustc")
+            .args(["--crate-type", "rlib", "--crate-name", self.name])
+            .args(["--edition", "2021", "-o"])
+            .arg(&temp_library)
+            .arg(&source_path)
+            .output()
+            .map_err(error)?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to build {}:\n{}",
+                self.name,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        fs::rename(&temp_library, &library).map_err(error)?;
+        Ok(library)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::exercise::{Exercise, ExerciseList};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The file listing the course packs, next to which rustlings is run
+pub const CONFIG_FILE: &str = "rustlings.toml";
+// The environment variable giving the root of the checkout, instead of
+// looking for it
+pub const ROOT_VAR: &str = "RUSTLINGS_ROOT";
+
+// The course packs rustlings can run, like the upstream exercises and the
+// exercises of an internal course
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // The course used without `--course`
+    pub default: Option<String>,
+    #[serde(default)]
+    pub courses: BTreeMap<String, Course>,
+}
+
+// A course pack, with its own info.toml and exercises
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Course {
+    // The directory of the info.toml of the course, which the paths of its
+    // exercises are relative to, itself relative to rustlings.toml
+    pub root: PathBuf,
+}
+
+// The progress of the learner in a course
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Config {
+    // The course packs of rustlings.toml, None if there is none
+    pub fn load() -> Result<Option<Config>, Box<dyn Error>> {
+        if !Path::new(CONFIG_FILE).exists() {
+            return Ok(None);
+        }
+        let config = fs::read_to_string(CONFIG_FILE)?;
+        Ok(Some(Config::parse(&config)?))
+    }
+
+    pub fn parse(config: &str) -> Result<Config, Box<dyn Error>> {
+        let config: Config = toml::from_str(config)?;
+        if let Some(default) = &config.default {
+            config.course(default)?;
+        }
+        Ok(config)
+    }
+
+    fn course(&self, name: &str) -> Result<&Course, String> {
+        self.courses.get(name).ok_or_else(|| {
+            format!(
+                "There is no course named {name} in {CONFIG_FILE}, the courses are: {}",
+                self.names().join(", ")
+            )
+        })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.courses.keys().map(String::as_str).collect()
+    }
+
+    // The course named `name`, or else the default course. None if there is
+    // no default, in which case the info.toml of the current directory is
+    // used.
+    pub fn select(&self, name: Option<&str>) -> Result<Option<(&str, &Course)>, String> {
+        let Some(name) = name.or(self.default.as_deref()) else {
+            return Ok(None);
+        };
+        let course = self.course(name)?;
+        let (name, _) = self.courses.get_key_value(name).unwrap();
+        Ok(Some((name, course)))
+    }
+}
+
+impl Course {
+    // How many exercises of the course the learner has done
+    pub fn progress(&self) -> Result<Progress, Box<dyn Error>> {
+        let exercises = ExerciseList::load(self.root.join("info.toml"))?.exercises;
+        let done = exercises
+            .iter()
+            .filter(|exercise| {
+                Exercise {
+                    path: self.root.join(&exercise.path),
+                    ..(*exercise).clone()
+                }
+                .looks_done()
+            })
+            .count();
+        Ok(Progress {
+            done,
+            total: exercises.len(),
+        })
+    }
+}
+
+// The root of the checkout rustlings is run in: the closest directory to
+// `start` with a rustlings.toml or an info.toml, walking up the tree. When a
+// course is chosen, only a rustlings.toml will do, to choose it from.
+pub fn find_root(start: &Path, needs_config: bool) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| {
+            dir.join(CONFIG_FILE).is_file() || (!needs_config && dir.join("info.toml").is_file())
+        })
+        .map(Path::to_path_buf)
+}
+
+// Print the courses with the progress of the learner in each, marking the
+// selected one
+pub fn list(config: &Config, selected: Option<&str>) {
+    println!("  {:<17}\t{:<30}\tProgress", "Course", "Root");
+    for (name, course) in &config.courses {
+        let marker = if Some(name.as_str()) == selected {
+            "*"
+        } else {
+            ""
+        };
+        let progress = match course.progress() {
+            Ok(Progress { done, total }) => format!(
+                "{done} / {total} exercises ({:.1} %)",
+                done as f32 / total.max(1) as f32 * 100.0
+            ),
+            Err(e) => format!("failed to load its info.toml: {e}"),
+        };
+        println!(
+            "{marker:<2}{name:<17}\t{:<30}\t{progress}",
+            course.root.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CONFIG: &str = r#"
+default = "upstream"
+
+[courses.upstream]
+root = "."
+
+[courses.internal]
+root = "courses/internal"
+"#;
+
+    #[test]
+    fn test_select_course() {
+        let config = Config::parse(CONFIG).unwrap();
+        assert_eq!(config.names(), vec!["internal", "upstream"]);
+        let (name, course) = config.select(None).unwrap().unwrap();
+        assert_eq!((name, course.root.as_path()), ("upstream", Path::new(".")));
+        let (name, course) = config.select(Some("internal")).unwrap().unwrap();
+        assert_eq!(
+            (name, course.root.as_path()),
+            ("internal", Path::new("courses/internal"))
+        );
+        assert!(config.select(Some("advanced")).is_err());
+    }
+
+    #[test]
+    fn test_select_without_default() {
+        let config = Config::parse("[courses.internal]\nroot = \"internal\"\n").unwrap();
+        assert_eq!(config.select(None), Ok(None));
+    }
+
+    #[test]
+    fn test_find_root() {
+        let fixture = Path::new("tests/fixture/courses").canonicalize().unwrap();
+        let exercises = fixture.join("extra/exercises");
+        assert_eq!(find_root(&exercises, false), Some(fixture.join("extra")));
+        assert_eq!(find_root(&exercises, true), Some(fixture.clone()));
+        assert_eq!(find_root(&fixture, false), Some(fixture.clone()));
+    }
+
+    #[test]
+    fn test_unknown_default_course() {
+        assert!(Config::parse("default = \"advanced\"\n").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::exercise::Exercise;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+// Where the profiles of the instrumented tests are written
+const COVERAGE_DIR: &str = "target/rustlings/coverage";
+// The environment variable telling instrumented binaries where to write
+// their profile, `%p` being replaced by the process id
+pub const PROFILE_VAR: &str = "LLVM_PROFILE_FILE";
+
+// The LLVM tools used to read the profiles
+pub struct Tools {
+    profdata: PathBuf,
+    cov: PathBuf,
+}
+
+// The LLVM tools shipped with rustup in the `llvm-tools` component, for the
+// version of LLVM rustc uses. None if it isn't installed.
+pub fn tools() -> Option<&'static Tools> {
+    static TOOLS: OnceLock<Option<Tools>> = OnceLock::new();
+    TOOLS
+        .get_or_init(|| {
+            let output = Command::new("rustc").arg("-vV").output().ok()?;
+            let version = String::from_utf8_lossy(&output.stdout).into_owned();
+            let host = version
+                .lines()
+                .find_map(|line| line.strip_prefix("host: "))?;
+            let output = Command::new("rustc")
+                .args(["--print", "sysroot"])
+                .output()
+                .ok()?;
+            let sysroot = String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string();
+            let bin = Path::new(&sysroot)
+                .join("lib")
+                .join("rustlib")
+                .join(host)
+                .join("bin");
+            let tools = Tools {
+                profdata: bin.join(format!("llvm-profdata{}", std::env::consts::EXE_SUFFIX)),
+                cov: bin.join(format!("llvm-cov{}", std::env::consts::EXE_SUFFIX)),
+            };
+            (tools.profdata.is_file() && tools.cov.is_file()).then_some(tools)
+        })
+        .as_ref()
+}
+
+// The directory the profiles of an exercise are written to
+fn profile_dir(exercise: &Exercise) -> PathBuf {
+    Path::new(COVERAGE_DIR).join(&exercise.name)
+}
+
+// Remove the profiles of earlier runs, and return the pattern of the
+// profiles of the next one, for `PROFILE_VAR`
+pub fn prepare(exercise: &Exercise) -> String {
+    let dir = profile_dir(exercise);
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("%p.profraw").display().to_string()
+}
+
+// Which lines of the exercise ran, by line number
+fn line_counts(
+    tools: &Tools,
+    exercise: &Exercise,
+    binary: &Path,
+) -> Result<BTreeMap<usize, u64>, String> {
+    let dir = profile_dir(exercise);
+    let profiles: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("The tests wrote no coverage profile: {e}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "profraw"))
+        .collect();
+    let profdata = dir.join("merged.profdata");
+    let output = Command::new(&tools.profdata)
+        .args(["merge", "-sparse", "-o"])
+        .arg(&profdata)
+        .args(&profiles)
+        .output()
+        .map_err(|e| format!("Failed to run llvm-profdata: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    let output = Command::new(&tools.cov)
+        .args(["export", "--format=lcov", "--instr-profile"])
+        .arg(&profdata)
+        .arg(binary)
+        .output()
+        .map_err(|e| format!("Failed to run llvm-cov: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    // The lcov format lists the source files, each followed by the number
+    // of times its lines ran, like `DA:12,3`
+    let exercise_path = fs::canonicalize(&exercise.path).ok();
+    let mut counts = BTreeMap::new();
+    let mut in_exercise = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(file) = line.strip_prefix("SF:") {
+            in_exercise = fs::canonicalize(file).ok() == exercise_path;
+        } else if let Some(data) = line.strip_prefix("DA:").filter(|_| in_exercise) {
+            let mut fields = data.split(',');
+            if let (Some(Ok(number)), Some(Ok(count))) = (
+                fields.next().map(str::parse::<usize>),
+                fields.next().map(str::parse::<u64>),
+            ) {
+                *counts.entry(number).or_default() += count;
+            }
+        }
+    }
+    Ok(counts)
+}
+
+// Measure the line coverage of the code of the exercise by its tests, once
+// they ran. The tests themselves don't count, which is everything from the
+// `#[cfg(test)]` line on. Ok holds the report if the coverage is at least
+// `minimum` percent, Err otherwise, like without the LLVM tools.
+pub fn check(exercise: &Exercise, minimum: f64, binary: &Path) -> Result<String, String> {
+    let Some(tools) = tools() else {
+        return Err(format!(
+            "The coverage of the tests of {exercise} can't be measured without the LLVM tools.\n\
+             Install them with `rustup component add llvm-tools`.\n"
+        ));
+    };
+    let source = fs::read_to_string(&exercise.path).map_err(|e| e.to_string())?;
+    let tests_start = source
+        .lines()
+        .position(|line| line.trim() == "#[cfg(test)]")
+        .map_or(usize::MAX, |i| i + 1);
+    let counts = line_counts(tools, exercise, binary)?;
+    let lines: Vec<(usize, u64)> = counts
+        .into_iter()
+        .filter(|&(number, _)| number < tests_start)
+        .collect();
+    if lines.is_empty() {
+        return Ok(String::new());
+    }
+    let covered = lines.iter().filter(|&&(_, count)| count > 0).count();
+    let percent = covered as f64 * 100.0 / lines.len() as f64;
+
+    let mut report = format!(
+        "Line coverage of {exercise}: {percent:.1}% ({covered}/{} lines, {minimum}% needed)\n",
+        lines.len()
+    );
+    let source_lines: Vec<&str> = source.lines().collect();
+    let uncovered: Vec<usize> = lines
+        .iter()
+        .filter(|&&(_, count)| count == 0)
+        .map(|&(number, _)| number)
+        .collect();
+    if !uncovered.is_empty() {
+        report += "Lines the tests don't run:\n";
+        for number in uncovered {
+            let line = source_lines.get(number - 1).copied().unwrap_or_default();
+            let _ = writeln!(report, "{number:>4} | {line}");
+        }
+    }
+    if percent >= minimum {
+        Ok(report)
+    } else {
+        Err(report)
+    }
+}
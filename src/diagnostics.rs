@@ -0,0 +1,343 @@
+use console::{style, StyledObject};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::path::Path;
+
+// Status lines cargo prints on stderr while it builds, which say nothing
+// about the exercise itself
+const CARGO_STATUS_PREFIXES: &[&str] = &[
+    "Compiling",
+    "Checking",
+    "Finished",
+    "Running",
+    "Blocking",
+    "Locking",
+    "Updating",
+    "Adding",
+    "Removed",
+    "Fresh",
+    "Doc-tests",
+    "error: could not compile",
+];
+
+// A diagnostic emitted by rustc or clippy, as found in their JSON output
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Diagnostic {
+    // The severity: "error", "warning", "note", "help" or "failure-note"
+    pub level: String,
+    // The error or lint code, like E0308 or clippy::float_cmp
+    #[serde(default, deserialize_with = "deserialize_code")]
+    pub code: Option<String>,
+    pub message: String,
+    pub spans: Vec<Span>,
+    // Notes, help messages and suggestions attached to the diagnostic
+    pub children: Vec<Diagnostic>,
+}
+
+// A region of a source file a diagnostic points to
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    // Whether this is the location the diagnostic is about, rather than
+    // a location giving more context
+    pub is_primary: bool,
+    pub label: Option<String>,
+    // The code the compiler suggests to put in place of the span
+    pub suggested_replacement: Option<String>,
+    #[serde(default, skip_serializing)]
+    text: Vec<SpanLine>,
+}
+
+// A line of source code covered by a span
+#[derive(Deserialize, Debug, Clone)]
+struct SpanLine {
+    text: String,
+    highlight_start: usize,
+    highlight_end: usize,
+}
+
+// rustc reports codes as `{"code": "E0308", "explanation": "..."}`
+fn deserialize_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Code {
+        code: String,
+    }
+    Ok(Option::<Code>::deserialize(deserializer)?.map(|c| c.code))
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level.starts_with("error")
+    }
+
+    // The spans the diagnostic is about
+    pub fn primary_spans(&self) -> impl Iterator<Item = &Span> {
+        self.spans.iter().filter(|span| span.is_primary)
+    }
+
+    // The replacements the compiler suggests, with the message explaining them
+    pub fn suggestions(&self) -> Vec<(&str, &Span)> {
+        self.children
+            .iter()
+            .flat_map(|child| {
+                child
+                    .spans
+                    .iter()
+                    .filter(|span| span.suggested_replacement.is_some())
+                    .map(move |span| (child.message.as_str(), span))
+            })
+            .collect()
+    }
+
+    // Make the paths of the spans relative to the current directory, like
+    // those of rustc. cargo reports them relative to the package the exercise
+    // is built in, given as `dir`, or as absolute paths for files outside of it.
+    pub fn relocate(&mut self, dir: &Path) {
+        let current_dir = env::current_dir().unwrap_or_default();
+        for span in self.spans.iter_mut().filter(|span| span.is_user_code()) {
+            let path = Path::new(&span.file_name);
+            if path.is_relative() {
+                span.file_name = dir.join(path).display().to_string();
+            } else if let Ok(path) = path.strip_prefix(&current_dir) {
+                span.file_name = path.display().to_string();
+            }
+        }
+        for child in &mut self.children {
+            child.relocate(dir);
+        }
+    }
+
+    // Summaries like "aborting due to 2 previous errors" and the pointer to
+    // `rustc --explain`, which rustlings has its own ways to show
+    pub fn is_noise(&self) -> bool {
+        self.spans.is_empty()
+            && (self.level == "failure-note"
+                || self.message.starts_with("aborting due to")
+                || self.message.ends_with("emitted"))
+    }
+
+    fn level_style<D>(&self, value: D) -> StyledObject<D> {
+        match self.level.as_str() {
+            "warning" => style(value).yellow().bold(),
+            "note" | "help" => style(value).cyan().bold(),
+            _ => style(value).red().bold(),
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let header = match &self.code {
+            Some(code) => format!("{}[{code}]", self.level),
+            None => self.level.clone(),
+        };
+        let _ = writeln!(
+            out,
+            "{}: {}",
+            self.level_style(header),
+            style(&self.message).bold()
+        );
+
+        let spans: Vec<&Span> = self
+            .spans
+            .iter()
+            .filter(|span| span.is_user_code())
+            .collect();
+        let width = spans
+            .iter()
+            .map(|span| span.line_end.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = style(format!("{:width$} |", "")).blue().bold();
+        if let Some(span) = self.primary_spans().next().or(spans.first().copied()) {
+            let _ = writeln!(
+                out,
+                "{:width$}{} {}:{}:{}",
+                "",
+                style("-->").blue().bold(),
+                span.file_name,
+                span.line_start,
+                span.column_start
+            );
+        }
+        if !spans.is_empty() {
+            let _ = writeln!(out, "{gutter}");
+        }
+        // Each source line is shown once, with the marks of all the spans
+        // on it below, the primary one first
+        let mut lines: BTreeMap<usize, (&str, Vec<String>)> = BTreeMap::new();
+        for span in spans
+            .iter()
+            .filter(|span| span.is_primary)
+            .chain(spans.iter().filter(|span| !span.is_primary))
+        {
+            for (offset, line) in span.text.iter().enumerate() {
+                let marker = if span.is_primary { "^" } else { "-" };
+                let start = line.highlight_start.saturating_sub(1);
+                let len = line
+                    .highlight_end
+                    .saturating_sub(line.highlight_start)
+                    .max(1);
+                let label = match &span.label {
+                    Some(label) if offset + 1 == span.text.len() => format!(" {label}"),
+                    _ => String::new(),
+                };
+                let marks = format!("{:start$}{}{label}", "", marker.repeat(len));
+                let marks = if span.is_primary {
+                    self.level_style(marks)
+                } else {
+                    style(marks).blue().bold()
+                };
+                lines
+                    .entry(span.line_start + offset)
+                    .or_insert_with(|| (&line.text, Vec::new()))
+                    .1
+                    .push(marks.to_string());
+            }
+        }
+        for (number, (text, marks)) in lines {
+            let number = style(format!("{number:>width$} |")).blue().bold();
+            let _ = writeln!(out, "{number} {text}");
+            for marks in marks {
+                let _ = writeln!(out, "{gutter} {marks}");
+            }
+        }
+        for child in &self.children {
+            let message = match child
+                .spans
+                .iter()
+                .find_map(|s| s.suggested_replacement.as_ref())
+            {
+                Some(replacement) => format!("{}: `{replacement}`", child.message),
+                None => child.message.clone(),
+            };
+            let _ = writeln!(
+                out,
+                "{:width$} {} {}: {message}",
+                "",
+                style("=").blue().bold(),
+                style(&child.level).bold()
+            );
+        }
+        out.push('\n');
+    }
+}
+
+impl Span {
+    // Whether the span points into the exercises rather than into the
+    // standard library, which shows up for errors inside macro expansions
+    fn is_user_code(&self) -> bool {
+        !self.file_name.starts_with("/rustc/") && !self.file_name.contains("/lib/rustlib/")
+    }
+}
+
+// Split the output of rustc with `--error-format=json`, or of cargo with
+// `--message-format=json`, into its diagnostics and the remaining lines,
+// like the output of the tests cargo ran.
+pub fn parse(output: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut rest = String::new();
+    for line in output.lines() {
+        let value = match serde_json::from_str::<Value>(line) {
+            Ok(value @ Value::Object(_)) => value,
+            _ => {
+                rest.push_str(line);
+                rest.push('\n');
+                continue;
+            }
+        };
+        let diagnostic = match value.get("reason").and_then(Value::as_str) {
+            // Messages about artifacts and build scripts are of no interest
+            Some("compiler-message") => value.get("message").cloned(),
+            Some(_) => None,
+            None => Some(value),
+        };
+        if let Some(diagnostic) = diagnostic.and_then(|d| serde_json::from_value(d).ok()) {
+            diagnostics.push(diagnostic);
+        }
+    }
+    (diagnostics, rest)
+}
+
+// Drop the status lines cargo prints while building
+pub fn strip_cargo_status(stderr: &str) -> String {
+    stderr
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !CARGO_STATUS_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+// Render the diagnostics the way rustc would, leaving out the noise
+pub fn render(diagnostics: &[Diagnostic]) -> String {
+    let mut out = String::new();
+    for diagnostic in diagnostics.iter().filter(|d| !d.is_noise()) {
+        diagnostic.render(&mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MISMATCHED_TYPES: &str = r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":"exercises/variables/variables1.rs","byte_start":94,"byte_end":97,"line_start":5,"line_end":5,"column_start":18,"column_end":21,"is_primary":true,"text":[{"text":"    let x: i32 = \"a\";","highlight_start":18,"highlight_end":21}],"label":"expected `i32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"try using a conversion method","code":null,"level":"help","spans":[{"file_name":"exercises/variables/variables1.rs","byte_start":94,"byte_end":97,"line_start":5,"line_end":5,"column_start":18,"column_end":21,"is_primary":true,"text":[],"label":null,"suggested_replacement":"\"a\".parse().unwrap()","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0308]: mismatched types\n"}"#;
+    const ABORTING: &str = r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}"#;
+
+    #[test]
+    fn test_parse_rustc_output() {
+        let output = format!("{MISMATCHED_TYPES}\n{ABORTING}\nnot json\n");
+        let (diagnostics, rest) = parse(&output);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(rest, "not json\n");
+        let diagnostic = &diagnostics[0];
+        assert!(diagnostic.is_error());
+        assert_eq!(diagnostic.code.as_deref(), Some("E0308"));
+        assert_eq!(diagnostic.primary_spans().next().unwrap().line_start, 5);
+        let suggestions = diagnostic.suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            suggestions[0].1.suggested_replacement.as_deref(),
+            Some("\"a\".parse().unwrap()")
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_output() {
+        let output = format!(
+            "{{\"reason\":\"compiler-artifact\",\"target\":{{}}}}\n{{\"reason\":\"compiler-message\",\"message\":{MISMATCHED_TYPES}}}\n"
+        );
+        let (diagnostics, rest) = parse(&output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "mismatched types");
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_render_highlights_primary_span() {
+        console::set_colors_enabled(false);
+        let (diagnostics, _) = parse(&format!("{MISMATCHED_TYPES}\n{ABORTING}"));
+        assert_eq!(
+            render(&diagnostics),
+            "error[E0308]: mismatched types\n \
+             --> exercises/variables/variables1.rs:5:18\n  \
+             |\n\
+             5 |     let x: i32 = \"a\";\n  \
+             |                  ^^^ expected `i32`, found `&str`\n  \
+             = help: try using a conversion method: `\"a\".parse().unwrap()`\n\n"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::coverage;
+use crate::exercise::miri_toolchain;
+use crate::project::{rust_src_install_command, rust_src_path};
+use console::style;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::process::Command;
+
+// A version of a tool, like the `1.58.0` of `rustc 1.58.0 (02072b482 2022-01-11)`
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl Version {
+    // Parse `1.58.0`, `1.58` or `1.60.0-nightly`
+    pub fn parse(version: &str) -> Option<Version> {
+        let version = version.split(['-', '+']).next()?;
+        let mut parts = version.split('.').map(str::parse::<u32>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = match parts.next() {
+            Some(patch) => patch.ok()?,
+            None => 0,
+        };
+        Some(Version {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    // Find the version in the output of `<tool> --version`
+    fn from_output(output: &str) -> Option<Version> {
+        output.split_whitespace().find_map(Version::parse)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+enum Status {
+    Ok,
+    // Some exercises won't work
+    Warning,
+    // No exercise will work
+    Error,
+}
+
+struct Check {
+    status: Status,
+    message: String,
+    // What to do about a warning or an error
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(message: String) -> Check {
+        Check {
+            status: Status::Ok,
+            message,
+            fix: None,
+        }
+    }
+
+    fn print(&self) {
+        let marker = match self.status {
+            Status::Ok => style("✓").green(),
+            Status::Warning => style("!").yellow(),
+            Status::Error => style("✗").red(),
+        };
+        println!("{marker} {}", self.message);
+        if let Some(fix) = &self.fix {
+            println!("  {fix}");
+        }
+    }
+}
+
+// Run `program args` and return its standard output, if it succeeded
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// The version of rustc, or None if it can't be run
+pub fn rustc_version() -> Option<Version> {
+    command_output("rustc", &["--version"]).and_then(|output| Version::from_output(&output))
+}
+
+// Check that rustc can be used for the exercises at all, before running any
+// command. The message explains what to do otherwise.
+pub fn check_rustc(min_version: Option<Version>) -> Result<(), String> {
+    let check = rustc_check(min_version);
+    match check.status {
+        Status::Error => Err(format!(
+            "{}\n{}",
+            check.message,
+            check.fix.unwrap_or_default()
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn rustc_check(min_version: Option<Version>) -> Check {
+    let Some(version) = rustc_version() else {
+        return Check {
+            status: Status::Error,
+            message: tr!("doctor.no_rustc"),
+            fix: Some(tr!("doctor.install_rust")),
+        };
+    };
+    match min_version {
+        Some(min_version) if version < min_version => Check {
+            status: Status::Error,
+            message: tr!(
+                "doctor.old_rustc",
+                version = version,
+                min_version = min_version
+            ),
+            fix: Some(tr!("doctor.update_rustc")),
+        },
+        Some(min_version) => Check::ok(tr!(
+            "doctor.rustc",
+            version = version,
+            min_version = min_version
+        )),
+        None => Check::ok(format!("rustc {version}")),
+    }
+}
+
+fn cargo_check() -> Check {
+    match command_output("cargo", &["--version"]).and_then(|output| Version::from_output(&output)) {
+        Some(version) => Check::ok(format!("cargo {version}")),
+        None => Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_cargo"),
+            fix: Some(tr!("doctor.install_cargo")),
+        },
+    }
+}
+
+fn clippy_check() -> Check {
+    match command_output("cargo", &["clippy", "--version"])
+        .and_then(|output| Version::from_output(&output))
+    {
+        Some(version) => Check::ok(format!("clippy {version}")),
+        None => Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_clippy"),
+            fix: Some(tr!("doctor.install_clippy")),
+        },
+    }
+}
+
+fn miri_check() -> Check {
+    match miri_toolchain() {
+        Some([]) => Check::ok("miri".to_string()),
+        Some(_) => Check::ok(tr!("doctor.nightly_miri")),
+        None => Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_miri"),
+            fix: Some(tr!("doctor.install_miri")),
+        },
+    }
+}
+
+fn llvm_tools_check() -> Check {
+    match coverage::tools() {
+        Some(_) => Check::ok("llvm-tools".to_string()),
+        None => Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_llvm_tools"),
+            fix: Some(tr!("doctor.install_llvm_tools")),
+        },
+    }
+}
+
+fn toolchain_check() -> Check {
+    match command_output("rustup", &["show", "active-toolchain"]) {
+        // Like `stable-x86_64-unknown-linux-gnu (default)`
+        Some(toolchain) => Check::ok(tr!("doctor.toolchain", toolchain = toolchain)),
+        None => Check {
+            status: Status::Ok,
+            message: tr!("doctor.no_rustup"),
+            fix: None,
+        },
+    }
+}
+
+fn rust_src_check() -> Check {
+    let Some(sysroot) = command_output("rustc", &["--print", "sysroot"]) else {
+        return Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_sysroot"),
+            fix: None,
+        };
+    };
+    let sysroot = Path::new(&sysroot);
+    if rust_src_path(sysroot).is_dir() {
+        Check::ok("rust-src".to_string())
+    } else {
+        Check {
+            status: Status::Warning,
+            message: tr!("doctor.no_rust_src"),
+            fix: Some(tr!(
+                "doctor.install_rust_src",
+                command = rust_src_install_command(sysroot)
+            )),
+        }
+    }
+}
+
+// Report on the tools the exercises need, and return whether the exercises
+// can be done with them
+pub fn doctor(min_version: Option<Version>) -> bool {
+    let checks = [
+        rustc_check(min_version),
+        cargo_check(),
+        clippy_check(),
+        miri_check(),
+        llvm_tools_check(),
+        toolchain_check(),
+        rust_src_check(),
+    ];
+    for check in &checks {
+        check.print();
+    }
+    let errors = checks
+        .iter()
+        .filter(|check| matches!(check.status, Status::Error))
+        .count();
+    let warnings = checks
+        .iter()
+        .filter(|check| matches!(check.status, Status::Warning))
+        .count();
+    println!();
+    match (errors, warnings) {
+        (0, 0) => println!("{}", tr!("doctor.ready")),
+        (0, _) => println!("{}", tr!("doctor.warnings")),
+        _ => println!("{}", tr!("doctor.errors")),
+    }
+    errors == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_parse_version() {
+        let version = Version::parse("1.58.1").unwrap();
+        assert_eq!(version.to_string(), "1.58.1");
+        assert_eq!(Version::parse("1.58"), Version::parse("1.58.0"));
+        assert_eq!(Version::parse("1.60.0-nightly"), Version::parse("1.60.0"));
+        assert_eq!(Version::parse("rustc"), None);
+    }
+
+    #[test]
+    fn test_version_from_output() {
+        let version = Version::from_output("rustc 1.58.1 (db9d1b20b 2022-01-20)").unwrap();
+        assert_eq!(
+            version.cmp(&Version::parse("1.58.0").unwrap()),
+            Ordering::Greater
+        );
+        assert!(version < Version::parse("1.60").unwrap());
+        let clippy = Version::from_output("clippy 0.1.58 (db9d1b2 2022-01-20)").unwrap();
+        assert_eq!(clippy.to_string(), "0.1.58");
+    }
+}
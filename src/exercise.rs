@@ -1,18 +1,35 @@
+use crate::benchmark::{self, Benchmark};
+use crate::bundled::{self, BundledCrate};
+use crate::coverage;
+use crate::diagnostics::{self, Diagnostic};
+use crate::i18n;
 use regex::Regex;
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
 use std::process::{self, Command};
+use std::sync::OnceLock;
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format", "json"];
+const CARGO_JSON_ARGS: &[&str] = &["--message-format", "json"];
+const DEFAULT_EDITION: &str = "2021";
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
 const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+// Where the cargo projects of the exercises using crates are generated
+const CARGO_PROJECTS_DIR: &str = "target/rustlings";
+// The crates the exercises can use, as written by `cargo vendor`
+const VENDOR_DIR: &str = "vendor";
+// How many seconds a future may take in an async exercise, unless set
+const DEFAULT_ASYNC_TIMEOUT: u64 = 10;
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -25,8 +42,25 @@ fn temp_file() -> String {
     format!("./temp_{}_{thread_id}", process::id())
 }
 
+// The arguments selecting a toolchain with Miri for cargo: none if the
+// default toolchain has it, otherwise nightly, where Miri is available.
+// None if Miri isn't installed.
+pub fn miri_toolchain() -> Option<&'static [&'static str]> {
+    static TOOLCHAIN: OnceLock<Option<&'static [&'static str]>> = OnceLock::new();
+    *TOOLCHAIN.get_or_init(|| {
+        let toolchains: [&'static [&'static str]; 2] = [&[], &["+nightly"]];
+        toolchains.into_iter().find(|toolchain| {
+            Command::new("cargo")
+                .args(*toolchain)
+                .args(["miri", "--version"])
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+    })
+}
+
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -39,14 +73,168 @@ pub enum Mode {
     BuildScript,
 }
 
+impl Mode {
+    // The name of the mode in info.toml
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Compile => "compile",
+            Mode::Test => "test",
+            Mode::Clippy => "clippy",
+            Mode::BuildScript => "buildscript",
+        }
+    }
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [Mode::Compile, Mode::Test, Mode::Clippy, Mode::BuildScript]
+            .into_iter()
+            .find(|mode| mode.name() == name)
+            .ok_or_else(|| {
+                format!("unknown mode `{name}`, expected compile, test, clippy or buildscript")
+            })
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ExerciseList {
+    // The oldest version of rustc the exercises can be done with
+    pub min_rustc_version: Option<String>,
+    // The options of all the exercises, which they can add to
+    #[serde(flatten)]
+    pub options: CompileOptions,
     pub exercises: Vec<Exercise>,
 }
 
+// How an exercise is compiled. These can be set for the whole course at
+// the top of info.toml, and for a single exercise.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct CompileOptions {
+    // The Rust edition, 2021 unless set
+    pub edition: Option<String>,
+    // Extra arguments to rustc, like `["-C", "overflow-checks=off"]`
+    #[serde(default)]
+    pub rustc_flags: Vec<String>,
+    // Configuration options to set, like `feature="pass"`, as taken by `--cfg`
+    #[serde(default)]
+    pub cfg: Vec<String>,
+    // Crates used by the exercise, like in the `[dependencies]` of a
+    // Cargo.toml. They are taken from the vendor directory of the course.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, toml::Value>,
+    // Whether the exercise is linked with the async runtime bundled with rustlings
+    #[serde(default, rename = "async")]
+    pub async_runtime: bool,
+    // How many seconds each future run by the async runtime may take
+    pub timeout: Option<u64>,
+    // Whether the tests can use the property testing harness bundled with
+    // rustlings
+    #[serde(default)]
+    pub property_tests: bool,
+    // Whether the tests are also run under Miri, which fails them on
+    // undefined behavior and memory leaks
+    #[serde(default)]
+    pub miri: bool,
+    // The seed of the property tests, instead of a random one per run, like
+    // the one `cicvverify` gives all the exercises
+    #[serde(skip)]
+    pub seed: Option<String>,
+}
+
+impl ExerciseList {
+    // Read and parse the exercise list from an info.toml file
+    pub fn load(path: impl AsRef<Path>) -> Result<ExerciseList, Box<dyn Error>> {
+        let toml_str = fs::read_to_string(path)?;
+        ExerciseList::parse(&toml_str)
+    }
+
+    // Parse the exercise list, applying the course-wide options to each exercise
+    pub fn parse(toml_str: &str) -> Result<ExerciseList, Box<dyn Error>> {
+        let mut list: ExerciseList = toml::from_str(toml_str)?;
+        for exercise in &mut list.exercises {
+            let options = &mut exercise.options;
+            if options.edition.is_none() {
+                options.edition = list.options.edition.clone();
+            }
+            options.async_runtime |= list.options.async_runtime;
+            options.property_tests |= list.options.property_tests;
+            options.miri |= list.options.miri;
+            if options.timeout.is_none() {
+                options.timeout = list.options.timeout;
+            }
+            options
+                .rustc_flags
+                .splice(0..0, list.options.rustc_flags.iter().cloned());
+            options.cfg.splice(0..0, list.options.cfg.iter().cloned());
+            // Clippy and build script exercises are built in the cargo
+            // project of their directory, which can't depend on crates
+            if let Mode::Clippy | Mode::BuildScript = exercise.mode {
+                if !options.dependencies.is_empty() {
+                    return Err(format!(
+                        "{} can't have dependencies, as an exercise of the {} mode",
+                        exercise.name,
+                        exercise.mode.name()
+                    )
+                    .into());
+                }
+                continue;
+            }
+            for (name, dependency) in &list.options.dependencies {
+                options
+                    .dependencies
+                    .entry(name.clone())
+                    .or_insert_with(|| dependency.clone());
+            }
+        }
+        Ok(list)
+    }
+}
+
+// The hint of an exercise, either a text or a text per language, like
+// `hint.en` and `hint.zh`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Hint {
+    Text(String),
+    Localized(BTreeMap<String, String>),
+}
+
+impl Hint {
+    // The hint in the language of the messages
+    pub fn text(&self) -> &str {
+        self.text_in(i18n::lang())
+    }
+
+    // The hint in `lang`, or else in English, or else in any language
+    fn text_in(&self, lang: &str) -> &str {
+        match self {
+            Hint::Text(text) => text,
+            Hint::Localized(texts) => texts
+                .get(lang)
+                .or_else(|| texts.get(i18n::DEFAULT_LANG))
+                .or_else(|| texts.values().next())
+                .map_or("", String::as_str),
+        }
+    }
+}
+
+impl From<&str> for Hint {
+    fn from(text: &str) -> Self {
+        Hint::Text(text.to_string())
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -55,7 +243,16 @@ pub struct Exercise {
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
     // The hint text associated with the exercise
-    pub hint: String,
+    pub hint: Hint,
+    // Explanations of error codes for this exercise, replacing the bundled ones
+    #[serde(default)]
+    pub explanations: HashMap<String, String>,
+    #[serde(flatten)]
+    pub options: CompileOptions,
+    // Timings the exercise has to meet once its tests pass
+    pub benchmark: Option<Benchmark>,
+    // The line coverage, in percent, its tests have to reach
+    pub coverage: Option<f64>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -99,6 +296,34 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The diagnostics of the compiler, when the exercise failed to compile
+    pub diagnostics: Vec<Diagnostic>,
+    // The reports of the stages run once the tests pass, like the timings
+    // of the benchmark
+    pub reports: Vec<String>,
+}
+
+impl ExerciseOutput {
+    // The output of an exercise which rustlings failed to build or run,
+    // explained on stderr
+    pub fn failure(stderr: String) -> Self {
+        ExerciseOutput {
+            stdout: String::new(),
+            stderr,
+            diagnostics: Vec::new(),
+            reports: Vec::new(),
+        }
+    }
+
+    // The seed a property test failed with, which gives the same inputs when
+    // set in the environment
+    pub fn failing_seed(&self) -> Option<String> {
+        let prefix = format!("{}=", bundled::SEED_VAR);
+        self.stdout
+            .split_whitespace()
+            .find_map(|word| word.strip_prefix(&prefix))
+            .map(str::to_string)
+    }
 }
 
 struct FileHandle;
@@ -110,29 +335,48 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        if self.uses_crates() && !Path::new(VENDOR_DIR).is_dir() {
+            return Err(ExerciseOutput::failure(format!(
+                "{self} uses crates, which are taken from the `{VENDOR_DIR}` directory, but it is missing.\n\
+                 Run `cargo vendor` in a project depending on the same crates and move its `vendor` directory here."
+            )));
+        }
         let cmd = match self.mode {
+            Mode::Compile | Mode::Test
+                if !self.bundled_crates().is_empty() && !self.uses_crates() =>
+            {
+                match self.bundled_crate_args() {
+                    Ok(args) => self.compile_with_bundled_crates(&args),
+                    Err(stderr) => return Err(ExerciseOutput::failure(stderr)),
+                }
+            }
+            // Failing to write or build the project is a failure of the
+            // exercise, not of rustlings
+            Mode::Compile | Mode::Test if self.uses_crates() => {
+                match self.compile_cargo_project() {
+                    Ok(output) => Ok(output),
+                    Err(e) => {
+                        clean();
+                        return Err(ExerciseOutput::failure(format!(
+                            "Failed to build the cargo project of {self}: {e}"
+                        )));
+                    }
+                }
+            }
             Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
+                .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(RUSTC_JSON_ARGS)
+                .args(self.rustc_args())
                 .output(),
             Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
+                .args(["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(RUSTC_JSON_ARGS)
+                .args(self.rustc_args())
+                .args(self.coverage_args())
                 .output(),
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
+                let cargo_toml = self.cargo_manifest();
                 let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
@@ -144,9 +388,9 @@ path = "{}.rs""#,
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
                 Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
                     .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
+                    .args(self.rustc_args())
                     .output()
                     .expect("Failed to compile!");
                 // Due to an issue with Clippy, a cargo clean is required to catch all lints.
@@ -154,27 +398,19 @@ path = "{}.rs""#,
                 // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
                 // https://github.com/rust-lang/rust-clippy/issues/3837
                 Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
                     .args(RUSTC_COLOR_ARGS)
                     .output()
                     .expect("Failed to run 'cargo clean'");
                 Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .args(["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(CARGO_JSON_ARGS)
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .envs(self.cargo_rustflags())
                     .output()
-            },
+            }
             Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
+                let cargo_toml = self.cargo_manifest();
                 let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
@@ -183,7 +419,9 @@ path = "{}.rs""#,
                 fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
 
                 Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
+                    .args(["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
+                    .args(CARGO_JSON_ARGS)
+                    .envs(self.cargo_rustflags())
                     .output()
             }
         }
@@ -196,37 +434,405 @@ path = "{}.rs""#,
             })
         } else {
             clean();
+            let stdout = String::from_utf8_lossy(&cmd.stdout);
+            let stderr = String::from_utf8_lossy(&cmd.stderr);
+            // rustc reports diagnostics on stderr, cargo on stdout, next to
+            // the output of the tests it ran
+            let (diagnostics, stdout, stderr) = match self.mode {
+                Mode::Compile | Mode::Test if !self.uses_crates() => {
+                    let (diagnostics, stderr) = diagnostics::parse(&stderr);
+                    (diagnostics, stdout.into_owned(), stderr)
+                }
+                _ => {
+                    let (mut diagnostics, stdout) = diagnostics::parse(&stdout);
+                    let dir = self.path.parent().unwrap_or(Path::new("."));
+                    diagnostics.iter_mut().for_each(|d| d.relocate(dir));
+                    (
+                        diagnostics,
+                        stdout,
+                        diagnostics::strip_cargo_status(&stderr),
+                    )
+                }
+            };
             Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                stdout,
+                stderr: diagnostics::render(&diagnostics) + &stderr,
+                diagnostics,
+                reports: Vec::new(),
             })
         }
     }
 
+    // Exercises using crates are built in a cargo project of their own
+    pub fn uses_crates(&self) -> bool {
+        !self.options.dependencies.is_empty()
+    }
+
+    pub fn cargo_project_dir(&self) -> PathBuf {
+        Path::new(CARGO_PROJECTS_DIR).join(&self.name)
+    }
+
+    // The manifest of the cargo project of an exercise using crates
+    pub fn cargo_project_manifest(&self) -> io::Result<String> {
+        let path = fs::canonicalize(&self.path)?;
+        let mut package = toml::value::Table::new();
+        package.insert("name".into(), self.name.clone().into());
+        package.insert("version".into(), "0.0.1".into());
+        package.insert("edition".into(), self.edition().into());
+        let mut bin = toml::value::Table::new();
+        bin.insert("name".into(), self.name.clone().into());
+        bin.insert("path".into(), path.display().to_string().into());
+        let mut dependencies: toml::value::Table = self
+            .options
+            .dependencies
+            .iter()
+            .map(|(name, dependency)| (name.clone(), dependency.clone()))
+            .collect();
+        // The bundled crates are built from their sources, like the exercise
+        for krate in self.bundled_crates() {
+            let dir = env::current_dir()?.join(krate.package_dir());
+            let mut dependency = toml::value::Table::new();
+            dependency.insert("path".into(), dir.display().to_string().into());
+            dependencies.insert(krate.name.into(), dependency.into());
+        }
+
+        let mut manifest = toml::value::Table::new();
+        manifest.insert("package".into(), package.into());
+        manifest.insert("bin".into(), vec![toml::Value::from(bin)].into());
+        manifest.insert("dependencies".into(), dependencies.into());
+        toml::to_string(&manifest).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // The cargo configuration of the project of an exercise using crates,
+    // which takes the crates from the vendor directory instead of crates.io
+    pub fn cargo_project_config(&self) -> io::Result<(PathBuf, String)> {
+        let vendor_dir = fs::canonicalize(VENDOR_DIR)?;
+        let config = format!(
+            "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n\
+             [source.vendored-sources]\ndirectory = {}\n",
+            toml::Value::from(vendor_dir.display().to_string())
+        );
+        Ok((
+            self.cargo_project_dir().join(".cargo").join("config.toml"),
+            config,
+        ))
+    }
+
+    // Write the cargo project of an exercise, with the configuration taking
+    // the crates from the vendor directory if it uses some
+    fn write_cargo_project(&self) -> io::Result<PathBuf> {
+        for krate in self.bundled_crates() {
+            krate.write_package()?;
+        }
+        let dir = self.cargo_project_dir();
+        fs::create_dir_all(dir.join(".cargo"))?;
+        let manifest_path = dir.join("Cargo.toml");
+        fs::write(&manifest_path, self.cargo_project_manifest()?)?;
+        if self.uses_crates() {
+            let (config_path, config) = self.cargo_project_config()?;
+            fs::write(config_path, config)?;
+        }
+        Ok(manifest_path)
+    }
+
+    // Run the tests of the exercise under Miri, in its cargo project
+    fn run_miri(&self, toolchain: &[&str]) -> io::Result<process::Output> {
+        let manifest_path = self.write_cargo_project()?;
+        let mut cmd = Command::new("cargo");
+        cmd.args(toolchain)
+            .args(["miri", "test", "--offline", "--bin", &self.name])
+            .arg("--manifest-path")
+            .arg(&manifest_path);
+        if self.uses_crates() {
+            cmd.arg("--config").arg(self.cargo_project_config()?.0);
+        }
+        cmd.envs(self.cargo_rustflags())
+            .envs(self.runtime_env())
+            .env(
+                "CARGO_TARGET_DIR",
+                Path::new(CARGO_PROJECTS_DIR).join("target"),
+            )
+            .output()
+    }
+
+    // Build the cargo project of an exercise using crates, offline, and put
+    // the executable where `run` expects it
+    fn compile_cargo_project(&self) -> io::Result<process::Output> {
+        let manifest_path = self.write_cargo_project()?;
+        let subcommand: &[&str] = match self.mode {
+            Mode::Test => &["test", "--no-run"],
+            _ => &["build"],
+        };
+        // cargo only reads the configuration of the current directory, so
+        // the one of the project is passed explicitly
+        let output = Command::new("cargo")
+            .args(subcommand)
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--config")
+            .arg(self.cargo_project_config()?.0)
+            .arg("--offline")
+            .args(CARGO_JSON_ARGS)
+            .envs(self.cargo_rustflags())
+            .env(
+                "CARGO_TARGET_DIR",
+                Path::new(CARGO_PROJECTS_DIR).join("target"),
+            )
+            .output()?;
+        if output.status.success() {
+            let executable = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .filter_map(|message| message.get("executable")?.as_str().map(PathBuf::from))
+                .next_back()
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "cargo built no executable")
+                })?;
+            fs::copy(executable, temp_file())?;
+        }
+        Ok(output)
+    }
+
+    // The crates bundled with rustlings the exercise is linked with
+    pub fn bundled_crates(&self) -> Vec<&'static BundledCrate> {
+        let mut crates = Vec::new();
+        if self.options.async_runtime {
+            crates.push(&bundled::RUNTIME);
+        }
+        if self.options.property_tests {
+            crates.push(&bundled::CHECK);
+        }
+        crates
+    }
+
+    // Build the bundled crates the exercise uses, and return the arguments
+    // linking them with rustc
+    pub fn bundled_crate_args(&self) -> Result<Vec<String>, String> {
+        let mut args = Vec::new();
+        for krate in self.bundled_crates() {
+            args.push("--extern".to_string());
+            args.push(format!("{}={}", krate.name, krate.build()?.display()));
+        }
+        Ok(args)
+    }
+
+    // Compile an exercise with rustc, linked with the bundled crates it uses
+    fn compile_with_bundled_crates(
+        &self,
+        bundled_crate_args: &[String],
+    ) -> io::Result<process::Output> {
+        let mut cmd = Command::new("rustc");
+        if let Mode::Test = self.mode {
+            cmd.arg("--test");
+        }
+        cmd.args([self.path.to_str().unwrap(), "-o", &temp_file()])
+            .args(RUSTC_JSON_ARGS)
+            .args(self.rustc_args())
+            .args(self.coverage_args())
+            .args(bundled_crate_args)
+            .output()
+    }
+
+    // The seed of the property tests, and the timeout the async runtime
+    // applies to each future it runs
+    fn runtime_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(seed) = &self.options.seed {
+            env.push((bundled::SEED_VAR, seed.clone()));
+        }
+        if self.options.async_runtime {
+            let timeout = self.options.timeout.unwrap_or(DEFAULT_ASYNC_TIMEOUT);
+            env.push((bundled::TIMEOUT_VAR, (timeout * 1000).to_string()));
+        }
+        env
+    }
+
+    // The Rust edition the exercise is written in
+    pub fn edition(&self) -> &str {
+        self.options.edition.as_deref().unwrap_or(DEFAULT_EDITION)
+    }
+
+    // The arguments to rustc for the edition, cfg and flags of the exercise
+    pub fn rustc_args(&self) -> Vec<String> {
+        let mut args = vec!["--edition".to_string(), self.edition().to_string()];
+        for cfg in &self.options.cfg {
+            args.push("--cfg".to_string());
+            args.push(cfg.clone());
+        }
+        args.extend(self.options.rustc_flags.iter().cloned());
+        args
+    }
+
+    // cargo takes the cfg and flags of the exercise from the environment.
+    // The edition is in the manifest instead.
+    fn cargo_rustflags(&self) -> Vec<(&'static str, String)> {
+        let args = self.rustc_args().split_off(2);
+        if args.is_empty() {
+            return Vec::new();
+        }
+        // Separated by 0x1f, so that the arguments may contain spaces
+        vec![("CARGO_ENCODED_RUSTFLAGS", args.join("\x1f"))]
+    }
+
+    // The Cargo.toml written next to clippy and build script exercises
+    // before they are compiled with cargo. Its binary is the exercise, which
+    // is next to it unless it's a solution checked by `selftest`.
+    pub fn cargo_manifest(&self) -> String {
+        let manifest_path = match self.mode {
+            Mode::Clippy => CLIPPY_CARGO_TOML_PATH,
+            _ => BUILD_SCRIPT_CARGO_TOML_PATH,
+        };
+        let manifest_dir: PathBuf = Path::new(manifest_path)
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .collect();
+        let path = match self.path.strip_prefix(&manifest_dir) {
+            Ok(path) => path.to_path_buf(),
+            Err(_) => manifest_dir
+                .components()
+                .map(|_| Component::ParentDir)
+                .collect::<PathBuf>()
+                .join(&self.path),
+        };
+        format!(
+            r#"[package]
+name = "{}"
+version = "0.0.1"
+edition = "{}"
+[[bin]]
+name = "{}"
+path = "{}""#,
+            self.name,
+            self.edition(),
+            self.name,
+            path.display()
+        )
+    }
+
     fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
         let arg = match self.mode {
             Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
-            }),
+            Mode::BuildScript => {
+                return Ok(ExerciseOutput {
+                    stdout: "".to_string(),
+                    stderr: "".to_string(),
+                    diagnostics: Vec::new(),
+                    reports: Vec::new(),
+                })
+            }
             _ => "",
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        let mut cmd = Command::new(temp_file());
+        cmd.arg(arg).envs(self.runtime_env());
+        if self.measures_coverage() {
+            cmd.env(coverage::PROFILE_VAR, coverage::prepare(self));
+        }
+        let cmd = cmd.output().expect("Failed to run 'run' command");
 
-        let output = ExerciseOutput {
+        let mut output = ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            diagnostics: Vec::new(),
+            reports: Vec::new(),
         };
 
-        if cmd.status.success() {
-            Ok(output)
+        if !cmd.status.success() {
+            return Err(output);
+        }
+        self.run_coverage_stage(&mut output)?;
+        self.run_miri_stage()?;
+        self.run_benchmark_stage(&mut output)?;
+        Ok(output)
+    }
+
+    // Whether the tests are instrumented to measure their coverage, which
+    // then needs the LLVM tools
+    fn measures_coverage(&self) -> bool {
+        matches!(self.mode, Mode::Test) && self.coverage.is_some() && !self.uses_crates()
+    }
+
+    // The arguments to rustc instrumenting the tests
+    fn coverage_args(&self) -> &'static [&'static str] {
+        if self.measures_coverage() {
+            &["-C", "instrument-coverage"]
         } else {
-            Err(output)
+            &[]
+        }
+    }
+
+    // First, the coverage of the exercise by its tests is measured
+    fn run_coverage_stage(&self, output: &mut ExerciseOutput) -> Result<(), ExerciseOutput> {
+        let Some(minimum) = self.coverage.filter(|_| self.measures_coverage()) else {
+            return Ok(());
+        };
+        match coverage::check(self, minimum, Path::new(&temp_file())) {
+            Ok(report) => {
+                output.reports.push(report);
+                Ok(())
+            }
+            Err(report) => Err(ExerciseOutput {
+                stdout: report,
+                stderr: String::new(),
+                diagnostics: Vec::new(),
+                reports: Vec::new(),
+            }),
+        }
+    }
+
+    // Once the tests pass, Miri checks that they don't rely on undefined
+    // behavior. Without Miri, passing the tests is enough.
+    fn run_miri_stage(&self) -> Result<(), ExerciseOutput> {
+        let toolchain = match self.mode {
+            Mode::Test if self.options.miri => miri_toolchain(),
+            _ => None,
+        };
+        let Some(toolchain) = toolchain else {
+            return Ok(());
+        };
+        let cmd = match self.run_miri(toolchain) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                return Err(ExerciseOutput::failure(format!(
+                    "Failed to run Miri on {self}: {e}"
+                )))
+            }
+        };
+        if cmd.status.success() {
+            return Ok(());
+        }
+        // The cargo project points at the exercise with an absolute path
+        let stderr = diagnostics::strip_cargo_status(&String::from_utf8_lossy(&cmd.stderr));
+        let stderr = match env::current_dir() {
+            Ok(dir) => stderr.replace(&format!("{}/", dir.display()), ""),
+            Err(_) => stderr,
+        };
+        Err(ExerciseOutput {
+            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+            stderr,
+            diagnostics: Vec::new(),
+            reports: Vec::new(),
+        })
+    }
+
+    // Then the exercise is timed against its reference solution, and the
+    // timings are added to the output
+    fn run_benchmark_stage(&self, output: &mut ExerciseOutput) -> Result<(), ExerciseOutput> {
+        let Some(benchmark) = self.benchmark.as_ref().filter(|_| !benchmark::deferred()) else {
+            return Ok(());
+        };
+        match benchmark::run(self, benchmark) {
+            Ok(report) => {
+                output.reports.push(report);
+                Ok(())
+            }
+            Err(report) => Err(ExerciseOutput {
+                stdout: report,
+                stderr: String::new(),
+                diagnostics: Vec::new(),
+                reports: Vec::new(),
+            }),
         }
     }
 
@@ -280,6 +886,113 @@ path = "{}.rs""#,
     pub fn looks_done(&self) -> bool {
         self.state() == State::Done
     }
+
+    // The chapter of the exercise, which is the directory it's in under
+    // `exercises/`. None for the exercises right in it, like the quizzes.
+    pub fn topic(&self) -> Option<&str> {
+        let dir = self.path.parent()?;
+        if dir.as_os_str().is_empty() || dir.ends_with("exercises") {
+            return None;
+        }
+        dir.file_name()?.to_str()
+    }
+
+    // The README of the chapter of the exercise, in the language of the
+    // messages if there is one, like `README.zh.md`
+    pub fn readme(&self) -> Option<PathBuf> {
+        let dir = self.path.parent()?;
+        [
+            format!("README.{}.md", i18n::lang()),
+            "README.md".to_string(),
+        ]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+    }
+
+    // Split the hint into its paragraphs, so that they can be revealed
+    // one step at a time
+    pub fn hints(&self) -> Vec<&str> {
+        self.hint
+            .text()
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect()
+    }
+}
+
+// Find the indices of the exercises that depend on the file at the given
+// (canonical) path. Besides the exercise files themselves, this covers the
+// build scripts and manifests of cargo based exercises, and modules shared by
+// the exercises of the closest directory above them.
+pub fn affected_exercises(exercises: &[Exercise], filepath: &Path) -> Vec<usize> {
+    // Files produced by cargo builds of the exercises are not dependencies
+    if filepath.components().any(|c| c.as_os_str() == "target") {
+        return Vec::new();
+    }
+    if let Some(index) = exercises.iter().position(|e| filepath.ends_with(&e.path)) {
+        return vec![index];
+    }
+    let (Some(dir), Some(file_name)) = (filepath.parent(), filepath.file_name()) else {
+        return Vec::new();
+    };
+    let in_dir = |dir: &Path, e: &Exercise| {
+        e.path
+            .parent()
+            .and_then(|parent| parent.canonicalize().ok())
+            .is_some_and(|parent| parent == dir)
+    };
+    let indices = |pred: &dyn Fn(&Exercise) -> bool| {
+        exercises
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| pred(e))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+    };
+
+    if file_name == "build.rs" {
+        return indices(&|e| matches!(e.mode, Mode::BuildScript) && in_dir(dir, e));
+    }
+    if file_name == "Cargo.toml" {
+        // Rustlings itself writes these manifests before compiling, so
+        // those writes should not be taken for edits
+        let manifest = fs::read_to_string(filepath).unwrap_or_default();
+        return indices(&|e| {
+            matches!(e.mode, Mode::Clippy | Mode::BuildScript)
+                && in_dir(dir, e)
+                && e.cargo_manifest() != manifest
+        });
+    }
+    if filepath.extension() == Some(OsStr::new("rs")) {
+        for ancestor in dir.ancestors() {
+            let shared = indices(&|e| in_dir(ancestor, e));
+            if !shared.is_empty() {
+                return shared;
+            }
+        }
+    }
+    Vec::new()
+}
+
+// For the tests of the modules using exercises: the exercise of the given
+// file and mode, named after the file, with no hint and no options
+#[cfg(test)]
+impl Exercise {
+    pub fn for_test(path: &str, mode: Mode) -> Exercise {
+        let path = PathBuf::from(path);
+        Exercise {
+            name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+            path,
+            mode,
+            hint: Hint::from(""),
+            explanations: HashMap::new(),
+            options: CompileOptions::default(),
+            benchmark: None,
+            coverage: None,
+        }
+    }
 }
 
 impl Display for Exercise {
@@ -290,7 +1003,7 @@ impl Display for Exercise {
 
 #[inline]
 fn clean() {
-    let _ignored = remove_file(&temp_file());
+    let _ignored = remove_file(temp_file());
 }
 
 #[cfg(test)]
@@ -300,13 +1013,8 @@ mod test {
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
+        File::create(temp_file()).unwrap();
+        let exercise = Exercise::for_test("tests/fixture/state/pending_exercise.rs", Mode::Compile);
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
@@ -314,12 +1022,7 @@ mod test {
 
     #[test]
     fn test_pending_state() {
-        let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("tests/fixture/state/pending_exercise.rs", Mode::Compile);
 
         let state = exercise.state();
         let expected = vec![
@@ -355,25 +1058,171 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise =
+            Exercise::for_test("tests/fixture/state/finished_exercise.rs", Mode::Compile);
 
         assert_eq!(exercise.state(), State::Done);
     }
 
     #[test]
-    fn test_exercise_with_output() {
+    fn test_topic() {
+        let topic = |path: &str| {
+            let exercise = Exercise::for_test(path, Mode::Compile);
+            exercise.topic().map(str::to_string)
+        };
+        assert_eq!(topic("exercises/tests/tests1.rs").as_deref(), Some("tests"));
+        assert_eq!(topic("exercises/quiz1.rs"), None);
+        assert_eq!(topic("compSuccess.rs"), None);
+    }
+
+    #[test]
+    fn test_localized_hint() {
+        let list = ExerciseList::parse(
+            r#"
+[[exercises]]
+name = "hinted_exercise"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "compile"
+hint.en = "Add a semicolon."
+hint.zh = "加一个分号。"
+"#,
+        )
+        .unwrap();
+        let hint = &list.exercises[0].hint;
+        assert_eq!(hint.text_in("zh"), "加一个分号。");
+        assert_eq!(hint.text_in("fr"), "Add a semicolon.");
+        assert_eq!(
+            Hint::from("Add a semicolon.").text_in("zh"),
+            "Add a semicolon."
+        );
+    }
+
+    #[test]
+    fn test_hints_are_split_into_paragraphs() {
         let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
+            hint: Hint::from("First step.\n\nSecond step,\nover two lines.\n\n"),
+            ..Exercise::for_test("tests/fixture/state/pending_exercise.rs", Mode::Compile)
         };
+
+        assert_eq!(
+            exercise.hints(),
+            vec!["First step.", "Second step,\nover two lines."]
+        );
+    }
+
+    #[test]
+    fn test_affected_exercises() {
+        let exercises = [
+            Exercise::for_test("tests/fixture/state/pending_exercise.rs", Mode::Compile),
+            Exercise::for_test("tests/fixture/state/pending_test_exercise.rs", Mode::Test),
+            Exercise::for_test("exercises/tests/build_script.rs", Mode::BuildScript),
+        ];
+        let affected =
+            |path: &str| affected_exercises(&exercises, &Path::new(path).canonicalize().unwrap());
+
+        assert_eq!(
+            affected("tests/fixture/state/pending_test_exercise.rs"),
+            vec![1]
+        );
+        // Not an exercise itself, so it counts as a module shared by its directory
+        assert_eq!(
+            affected("tests/fixture/state/finished_exercise.rs"),
+            vec![0, 1]
+        );
+        assert_eq!(affected("exercises/tests/build.rs"), vec![2]);
+        assert_eq!(affected("exercises/tests/Cargo.toml"), vec![2]);
+        assert_eq!(
+            affected("tests/fixture/state/info.toml"),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            affected_exercises(
+                &exercises,
+                Path::new("/exercises/tests/target/debug/build/out.rs")
+            ),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_exercise_with_output() {
+        let exercise = Exercise::for_test("tests/fixture/success/testSuccess.rs", Mode::Test);
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_course_options_apply_to_exercises() {
+        let list = ExerciseList::parse(
+            r#"
+edition = "2018"
+rustc_flags = ["-C", "overflow-checks=on"]
+
+[[exercises]]
+name = "first"
+path = "first.rs"
+mode = "compile"
+hint = ""
+
+[[exercises]]
+name = "second"
+path = "second.rs"
+mode = "test"
+hint = ""
+edition = "2024"
+rustc_flags = ["-A", "warnings"]
+cfg = ['feature="pass"']
+"#,
+        )
+        .unwrap();
+        let [first, second] = &list.exercises[..] else {
+            panic!("expected two exercises");
+        };
+        assert_eq!(
+            first.rustc_args(),
+            ["--edition", "2018", "-C", "overflow-checks=on"]
+        );
+        assert_eq!(
+            second.rustc_args(),
+            [
+                "--edition",
+                "2024",
+                "--cfg",
+                "feature=\"pass\"",
+                "-C",
+                "overflow-checks=on",
+                "-A",
+                "warnings"
+            ]
+        );
+        assert!(second.cargo_manifest().contains("edition = \"2024\""));
+    }
+
+    #[test]
+    fn test_dependencies_of_cargo_exercises() {
+        let parse = |course: &str, own: &str| {
+            ExerciseList::parse(&format!(
+                "{course}\n[[exercises]]\nname = \"clippy1\"\npath = \"clippy1.rs\"\nmode = \"clippy\"\nhint = \"\"\n{own}\n"
+            ))
+        };
+        let rand = r#"dependencies = { rand = "0.8" }"#;
+        // Course-wide ones are left out, the exercise's own are an error
+        assert!(!parse(rand, "").unwrap().exercises[0].uses_crates());
+        assert!(parse("", rand).is_err());
+    }
+
+    #[test]
+    fn test_failing_seed() {
+        let output = |stdout: &str| ExerciseOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            diagnostics: Vec::new(),
+            reports: Vec::new(),
+        };
+        let failed = output(
+            "smallest failing input: [0]\nrerun with RUSTLINGS_SEED=0x2a to get the same inputs\n",
+        );
+        assert_eq!(failed.failing_seed().as_deref(), Some("0x2a"));
+        assert_eq!(output("test result: FAILED").failing_seed(), None);
+    }
 }
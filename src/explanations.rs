@@ -0,0 +1,114 @@
+use crate::diagnostics::Diagnostic;
+use crate::exercise::Exercise;
+use console::style;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+// The explanations shipped with rustlings, see explanations.toml
+const BUNDLED: &str = include_str!("../explanations.toml");
+
+#[derive(Deserialize)]
+struct Explanations {
+    // Explanations by error code
+    codes: HashMap<String, String>,
+    // Explanations by topic, then error code
+    #[serde(default)]
+    topics: HashMap<String, HashMap<String, String>>,
+}
+
+fn bundled() -> &'static Explanations {
+    static EXPLANATIONS: OnceLock<Explanations> = OnceLock::new();
+    EXPLANATIONS.get_or_init(|| toml::from_str(BUNDLED).expect("explanations.toml is invalid"))
+}
+
+// Find the explanation of an error code for the given exercise. Those from
+// info.toml come first, then those of the exercise's topic, then the general ones.
+pub fn explain<'a>(exercise: &'a Exercise, code: &str) -> Option<&'a str> {
+    let explanations = bundled();
+    let topic = exercise
+        .path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|dir| dir.to_str())
+        .and_then(|dir| explanations.topics.get(dir));
+    exercise
+        .explanations
+        .get(code)
+        .or_else(|| topic.and_then(|topic| topic.get(code)))
+        .or_else(|| explanations.codes.get(code))
+        .map(|explanation| explanation.trim())
+}
+
+// The explanations of the errors the exercise failed to compile with, to show
+// beneath the compiler output. Empty if none of the errors has one.
+pub fn explanations(exercise: &Exercise, diagnostics: &[Diagnostic]) -> String {
+    let mut codes: Vec<&str> = Vec::new();
+    for code in diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .filter_map(|d| d.code.as_deref())
+    {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    let mut out = String::new();
+    for code in codes {
+        if let Some(explanation) = explain(exercise, code) {
+            let _ = writeln!(
+                out,
+                "{}",
+                style(format!("What {code} means:")).cyan().bold()
+            );
+            let _ = writeln!(out, "{explanation}\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise::for_test(path, Mode::Compile)
+    }
+
+    #[test]
+    fn test_bundled_explanations_parse() {
+        assert!(!bundled().codes.is_empty());
+    }
+
+    #[test]
+    fn test_explanations_are_looked_up_by_topic_then_code() {
+        let general = bundled().codes["E0382"].trim();
+        let topic = bundled().topics["move_semantics"]["E0382"].trim();
+        assert_eq!(
+            explain(&exercise("exercises/variables/variables1.rs"), "E0382"),
+            Some(general)
+        );
+        assert_eq!(
+            explain(
+                &exercise("exercises/move_semantics/move_semantics1.rs"),
+                "E0382"
+            ),
+            Some(topic)
+        );
+        assert_eq!(
+            explain(&exercise("exercises/variables/variables1.rs"), "E9999"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exercise_explanations_override_bundled_ones() {
+        let mut exercise = exercise("exercises/move_semantics/move_semantics1.rs");
+        exercise
+            .explanations
+            .insert("E0382".into(), "Clone it!".into());
+        assert_eq!(explain(&exercise, "E0382"), Some("Clone it!"));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// When the exercises last passed and were last checked, in seconds since
+// the Unix epoch, by name
+const HISTORY_FILE: &str = "target/rustlings/verified.json";
+
+// When an exercise was last checked, and how that went
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passed: Option<u64>,
+    pub checked: u64,
+    // Whether the last check failed. Several checks can happen in the same
+    // second, so this can't be told from the times.
+    pub last_failed: bool,
+}
+
+impl Record {
+    fn update(&mut self, passed: bool, now: u64) {
+        if passed {
+            self.passed = Some(now);
+        }
+        self.checked = now;
+        self.last_failed = !passed;
+    }
+}
+
+// When each exercise was last checked
+pub fn load() -> BTreeMap<String, Record> {
+    fs::read_to_string(HISTORY_FILE)
+        .ok()
+        .and_then(|history| serde_json::from_str(&history).ok())
+        .unwrap_or_default()
+}
+
+// Record whether the exercise passed just now. The history is only a
+// convenience, so failing to write it isn't an error.
+pub fn record(exercise: &Exercise, passed: bool) {
+    // The exercises may be checked from several threads at once
+    static LOCK: Mutex<()> = Mutex::new(());
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let mut history = load();
+    history
+        .entry(exercise.name.clone())
+        .or_default()
+        .update(passed, now);
+
+    let path = Path::new(HISTORY_FILE);
+    let temp_path = path.with_extension(format!("{}.json", process::id()));
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&temp_path, serde_json::to_string_pretty(&history).unwrap()))
+        .and_then(|_| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+}
+
+// A timestamp as an ISO 8601 date and time in UTC, like 2024-03-01T12:30:00Z
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let time = secs % 86400;
+    // The civil date of a number of days since 1970-01-01, from
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_296_200), "2024-03-01T12:30:00Z");
+    }
+
+    #[test]
+    fn test_update() {
+        let mut record = Record::default();
+        record.update(false, 1);
+        assert!(record.last_failed);
+        assert_eq!(record.passed, None);
+        // Fixed within the same second
+        record.update(true, 1);
+        assert!(!record.last_failed);
+        record.update(false, 2);
+        assert!(record.last_failed);
+        assert_eq!((record.passed, record.checked), (Some(1), 2));
+    }
+}
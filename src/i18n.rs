@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// The language the messages fall back to
+pub const DEFAULT_LANG: &str = "en";
+
+// The messages of rustlings by language, see locales/en.toml
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("zh", include_str!("../locales/zh.toml")),
+];
+
+static LANG: OnceLock<String> = OnceLock::new();
+
+// Translate a message of the catalog, replacing its `{name}` placeholders
+// with the values given, like `tr!("verify.ran", exercise = exercise)`
+macro_rules! tr {
+    ($key:literal) => {
+        $crate::i18n::message($key).to_string()
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::message($key),
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+// The language of a locale like `zh_CN.UTF-8`
+fn language(locale: &str) -> Option<String> {
+    let language = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    // The C locale means no language in particular
+    (!language.is_empty() && language != "c" && language != "posix").then_some(language)
+}
+
+// Choose the language of the messages, instead of the one of the locale.
+// Only the first choice counts.
+pub fn set_lang(lang: &str) {
+    let _ = LANG.set(language(lang).unwrap_or_else(|| DEFAULT_LANG.to_string()));
+}
+
+// Choose the language of the messages when the locale in the environment
+// has none, like the `C.UTF-8` of CI runners
+pub fn set_default_lang(lang: &str) {
+    if locale_language().is_none() {
+        set_lang(lang);
+    }
+}
+
+// The language of the locale in the environment
+fn locale_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .and_then(|locale| language(&locale))
+}
+
+// The language of the messages: the one chosen with `--lang`, or else the
+// one of the locale in the environment
+pub fn lang() -> &'static str {
+    LANG.get_or_init(|| locale_language().unwrap_or_else(|| DEFAULT_LANG.to_string()))
+}
+
+// The messages of a catalog, with keys like `verify.ran` for `ran` in the
+// `[verify]` table
+fn parse(catalog: &str) -> HashMap<String, String> {
+    let table: toml::value::Table = toml::from_str(catalog).expect("Invalid message catalog");
+    let mut messages = HashMap::new();
+    for (section, value) in table {
+        if let toml::Value::Table(entries) = value {
+            for (key, value) in entries {
+                if let toml::Value::String(message) = value {
+                    messages.insert(format!("{section}.{key}"), message);
+                }
+            }
+        }
+    }
+    messages
+}
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static PARSED: OnceLock<HashMap<&str, HashMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|&(lang, catalog)| (lang, parse(catalog)))
+            .collect()
+    })
+}
+
+// The message `key` in the language of the messages, or else in English
+pub fn message(key: &str) -> &'static str {
+    let catalogs = catalogs();
+    [lang(), DEFAULT_LANG]
+        .iter()
+        .find_map(|lang| catalogs.get(lang)?.get(key))
+        .map_or_else(|| panic!("There is no message {key}"), String::as_str)
+}
+
+// Replace the `{name}` placeholders of a message
+pub fn fill(message: &str, values: &[(&str, &dyn Display)]) -> String {
+    let mut message = message.to_string();
+    for (name, value) in values {
+        message = message.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    message
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_language() {
+        assert_eq!(language("zh_CN.UTF-8").as_deref(), Some("zh"));
+        assert_eq!(language("en").as_deref(), Some("en"));
+        assert_eq!(language("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(language("C.UTF-8"), None);
+        assert_eq!(language(""), None);
+    }
+
+    #[test]
+    fn test_catalogs_have_english_messages() {
+        let catalogs = catalogs();
+        let english = &catalogs[DEFAULT_LANG];
+        for (lang, messages) in catalogs {
+            for key in messages.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{key} of {lang} isn't in English"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill(
+                "Ran {exercise} in {seconds} s",
+                &[("exercise", &"intro1"), ("seconds", &2)]
+            ),
+            "Ran intro1 in 2 s"
+        );
+    }
+}
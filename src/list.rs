@@ -0,0 +1,315 @@
+use crate::exercise::Exercise;
+use crate::history;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::str::FromStr;
+
+// The smallest widths of the name and path columns of the table
+const NAME_WIDTH: usize = 17;
+const PATH_WIDTH: usize = 46;
+
+// How `rustlings list` prints the exercises
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    // A table for humans
+    Table,
+    // An array of objects, for scripts
+    Json,
+    // Comma separated values with a header line, for scripts and spreadsheets
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format `{format}`, expected table, json or csv"
+            )),
+        }
+    }
+}
+
+// What the exercises are sorted by, instead of the order of info.toml
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Name,
+    Path,
+    Topic,
+    // The pending exercises first
+    Status,
+    // The most recently verified first
+    Verified,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        match key {
+            "name" => Ok(SortKey::Name),
+            "path" => Ok(SortKey::Path),
+            "topic" => Ok(SortKey::Topic),
+            "status" => Ok(SortKey::Status),
+            "verified" => Ok(SortKey::Verified),
+            _ => Err(format!(
+                "unknown sort key `{key}`, expected name, path, topic, status or verified"
+            )),
+        }
+    }
+}
+
+// What the rows of the table are grouped by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    Topic,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        match key {
+            "topic" => Ok(GroupBy::Topic),
+            _ => Err(format!("unknown grouping `{key}`, expected topic")),
+        }
+    }
+}
+
+// An exercise as listed
+#[derive(Serialize)]
+struct Entry<'a> {
+    name: &'a str,
+    path: String,
+    mode: &'static str,
+    topic: Option<&'a str>,
+    // "done" or "pending"
+    status: &'static str,
+    // When the exercise last passed, in ISO 8601
+    last_verified: Option<String>,
+    // How many steps its hint has
+    hints: usize,
+    #[serde(skip)]
+    verified_at: Option<u64>,
+}
+
+impl<'a> Entry<'a> {
+    fn new(exercise: &'a Exercise, history: &BTreeMap<String, history::Record>) -> Self {
+        let verified_at = history.get(&exercise.name).and_then(|record| record.passed);
+        Entry {
+            name: &exercise.name,
+            path: exercise.path.display().to_string(),
+            mode: exercise.mode.name(),
+            topic: exercise.topic(),
+            status: if exercise.looks_done() {
+                "done"
+            } else {
+                "pending"
+            },
+            last_verified: verified_at.map(history::format_timestamp),
+            hints: exercise.hints().len(),
+            verified_at,
+        }
+    }
+}
+
+// How `rustlings list` prints the exercises
+pub struct ListOptions {
+    pub format: Format,
+    pub sort: Option<SortKey>,
+    pub group_by: Option<GroupBy>,
+    // Only the paths of the exercises, in the table format
+    pub paths: bool,
+    // Only the names of the exercises, in the table format
+    pub names: bool,
+}
+
+// Somehow using println! leads to the binary panicking when its output is
+// piped. So, we're handling a Broken Pipe error and exiting with 0 anyway
+fn write_out(text: &str) {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    handle.write_all(text.as_bytes()).unwrap_or_else(|e| {
+        match e.kind() {
+            std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+            _ => std::process::exit(1),
+        };
+    });
+}
+
+fn sort(entries: &mut [Entry], key: SortKey) {
+    match key {
+        SortKey::Name => entries.sort_by(|a, b| a.name.cmp(b.name)),
+        SortKey::Path => entries.sort_by(|a, b| a.path.cmp(&b.path)),
+        // The exercises without a topic last
+        SortKey::Topic => entries.sort_by_key(|e| (e.topic.is_none(), e.topic)),
+        SortKey::Status => entries.sort_by_key(|e| e.status == "done"),
+        SortKey::Verified => entries.sort_by_key(|e| std::cmp::Reverse(e.verified_at)),
+    }
+}
+
+// A field of a CSV line, quoted if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv(entries: &[Entry]) -> String {
+    let mut csv = String::from("name,path,mode,topic,status,last_verified,hints\n");
+    for entry in entries {
+        let fields = [
+            entry.name,
+            &entry.path,
+            entry.mode,
+            entry.topic.unwrap_or_default(),
+            entry.status,
+            entry.last_verified.as_deref().unwrap_or_default(),
+            &entry.hints.to_string(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv += &fields.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+fn table(entries: &[Entry], options: &ListOptions) -> String {
+    let mut table = String::new();
+    if options.paths || options.names {
+        for entry in entries {
+            table += if options.paths {
+                &entry.path
+            } else {
+                entry.name
+            };
+            table.push('\n');
+        }
+        return table;
+    }
+
+    let name_width = entries
+        .iter()
+        .map(|e| e.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(NAME_WIDTH);
+    let path_width = entries
+        .iter()
+        .map(|e| e.path.len())
+        .max()
+        .unwrap_or(0)
+        .max(PATH_WIDTH);
+    let row = |entry: &Entry| {
+        let status = if entry.status == "done" {
+            tr!("list.done")
+        } else {
+            tr!("list.pending")
+        };
+        format!(
+            "{:<name_width$}\t{:<path_width$}\t{status:<7}\n",
+            entry.name, entry.path
+        )
+    };
+    table += &format!(
+        "{:<name_width$}\t{:<path_width$}\t{:<7}\n",
+        tr!("list.name"),
+        tr!("list.path"),
+        tr!("list.status")
+    );
+    match options.group_by {
+        None => entries.iter().for_each(|entry| table += &row(entry)),
+        Some(GroupBy::Topic) => {
+            // The topics in the order they first appear in
+            let mut topics: Vec<Option<&str>> = Vec::new();
+            for entry in entries {
+                if !topics.contains(&entry.topic) {
+                    topics.push(entry.topic);
+                }
+            }
+            for topic in topics {
+                let group: Vec<&Entry> = entries.iter().filter(|e| e.topic == topic).collect();
+                let done = group.iter().filter(|e| e.status == "done").count();
+                let other = tr!("list.other");
+                let topic = topic.unwrap_or(&other);
+                table += &format!("\n{topic} ({done}/{})\n", group.len());
+                group.into_iter().for_each(|entry| table += &row(entry));
+            }
+        }
+    }
+    table
+}
+
+// Print the exercises in the format of the options
+pub fn list(exercises: &[&Exercise], options: &ListOptions) {
+    let history = history::load();
+    let mut entries: Vec<Entry> = exercises
+        .iter()
+        .map(|exercise| Entry::new(exercise, &history))
+        .collect();
+    if let Some(key) = options.sort {
+        sort(&mut entries, key);
+    }
+    let output = match options.format {
+        Format::Table => table(&entries, options),
+        Format::Json => serde_json::to_string_pretty(&entries).unwrap() + "\n",
+        Format::Csv => csv(&entries),
+    };
+    write_out(&output);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(
+        name: &'static str,
+        topic: Option<&'static str>,
+        verified_at: Option<u64>,
+    ) -> Entry<'static> {
+        Entry {
+            name,
+            path: format!("exercises/{name}.rs"),
+            mode: "compile",
+            topic,
+            status: "pending",
+            last_verified: verified_at.map(history::format_timestamp),
+            hints: 1,
+            verified_at,
+        }
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let mut quoted = entry("quoted", Some("a,b"), Some(0));
+        quoted.path = String::from("say \"hi\".rs");
+        assert_eq!(
+            csv(&[quoted]),
+            "name,path,mode,topic,status,last_verified,hints\n\
+             quoted,\"say \"\"hi\"\".rs\",compile,\"a,b\",pending,1970-01-01T00:00:00Z,1\n"
+        );
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut entries = vec![
+            entry("b", None, Some(1)),
+            entry("c", Some("tests"), None),
+            entry("a", Some("intro"), Some(2)),
+        ];
+        let names = |entries: &[Entry<'static>]| entries.iter().map(|e| e.name).collect::<Vec<_>>();
+        sort(&mut entries, SortKey::Name);
+        assert_eq!(names(&entries), ["a", "b", "c"]);
+        sort(&mut entries, SortKey::Topic);
+        assert_eq!(names(&entries), ["a", "c", "b"]);
+        sort(&mut entries, SortKey::Verified);
+        assert_eq!(names(&entries), ["a", "b", "c"]);
+    }
+}
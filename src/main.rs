@@ -1,31 +1,52 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::course::Config;
+use crate::diagnostics::Diagnostic;
+use crate::doctor::{check_rustc, doctor, Version};
+use crate::exercise::{affected_exercises, Exercise, ExerciseList, Mode};
+use crate::list::{Format, GroupBy, ListOptions, SortKey};
 use crate::project::RustAnalyzerProject;
 use crate::run::{reset, run};
+use crate::select::Selector;
+use crate::shell::{spawn_watch_shell, FocusCommand, WatchState};
 use crate::verify::verify;
+use crate::watcher::watch_exercises;
 use argh::FromArgs;
 use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
+use std::collections::HashSet;
+use std::env;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
-use std::time::{UNIX_EPOCH, SystemTime};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[macro_use]
 mod ui;
-
+#[macro_use]
+mod i18n;
+
+mod benchmark;
+mod bundled;
+mod course;
+mod coverage;
+mod diagnostics;
+mod doctor;
 mod exercise;
+mod explanations;
+mod history;
+mod list;
 mod project;
 mod run;
+mod scaffold;
+mod select;
+mod selftest;
+mod server;
+mod shell;
+mod tui;
 mod verify;
+mod watcher;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +60,12 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the course pack of rustlings.toml to use
+    #[argh(option)]
+    course: Option<String>,
+    /// the language of the messages and hints, like en or zh, instead of the one of LANG
+    #[argh(option)]
+    lang: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -53,17 +80,32 @@ enum Subcommands {
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    Serve(ServeArgs),
+    Doctor(DoctorArgs),
+    New(NewArgs),
+    Selftest(SelftestArgs),
+    CicvVerify(CicvVerifyArgs),
+    Courses(CoursesArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "cicvverify", description = "cicvverify")]
 struct CicvVerifyArgs {}
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "courses")]
+/// Lists the course packs of rustlings.toml and the progress in each
+struct CoursesArgs {}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(positional)]
+    /// the exercises: names, topics, globs like algorithm*, /regexes/,
+    /// next, current or failed, comma separated, all of them by default
+    selector: Option<String>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,39 +114,100 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// use the full-screen terminal interface
+    #[argh(switch)]
+    tui: bool,
+    /// only verify the most recently saved exercise
+    #[argh(switch)]
+    focus: bool,
+    /// poll for file changes, for filesystems without change notifications
+    #[argh(switch)]
+    poll: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
-/// Runs/Tests a single exercise
+/// Runs/Tests exercises
 struct RunArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the exercises: names, topics, globs like algorithm*, /regexes/,
+    /// next, current or failed, comma separated
     name: String,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets exercises using "git stash -- <filename>"
 struct ResetArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the exercises: names, topics, globs like algorithm*, /regexes/,
+    /// next, current or failed, comma separated
     name: String,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "hint")]
-/// Returns a hint for the given exercise
+/// Returns the hints of the given exercises
 struct HintArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the exercises: names, topics, globs like algorithm*, /regexes/,
+    /// next, current or failed, comma separated
     name: String,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
-struct LspArgs {}
+struct LspArgs {
+    /// only report whether the generated files are up to date
+    #[argh(switch)]
+    check: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "doctor")]
+/// Checks that the tools needed for the exercises are installed
+struct DoctorArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "new")]
+/// Creates an exercise and adds it to info.toml
+struct NewArgs {
+    #[argh(positional)]
+    /// the chapter and name of the exercise, like tests/tests10
+    exercise: String,
+    /// the mode of the exercise: compile, test or clippy
+    #[argh(option, default = "Mode::Compile")]
+    mode: Mode,
+    /// the exercise to add it after, by default the last of its chapter
+    #[argh(option)]
+    after: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "selftest")]
+/// Checks that every exercise fails unsolved and passes with its solution
+struct SelftestArgs {
+    #[argh(option, short = 'f')]
+    /// only test these exercises: names, topics, globs like algorithm*,
+    /// /regexes/, next, current or failed, comma separated
+    filter: Option<String>,
+    #[argh(option)]
+    /// a rustup toolchain to test with, repeat it to compare toolchains
+    toolchain: Vec<String>,
+    #[argh(switch)]
+    /// print the results as JSON
+    json: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "serve")]
+/// Answers requests from editors about the exercises over JSON-RPC
+struct ServeArgs {
+    /// communicate over stdin and stdout
+    #[argh(switch)]
+    stdio: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
@@ -117,8 +220,8 @@ struct ListArgs {
     /// show only the names of the exercises
     names: bool,
     #[argh(option, short = 'f')]
-    /// provide a string to match exercise names
-    /// comma separated patterns are acceptable
+    /// only list these exercises: names, topics, globs like algorithm*,
+    /// /regexes/, next, current or failed, comma separated
     filter: Option<String>,
     #[argh(switch, short = 'u')]
     /// display only exercises not yet solved
@@ -126,23 +229,38 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(option, default = "Format::Table")]
+    /// the output format: table, json or csv
+    format: Format,
+    #[argh(option)]
+    /// sort the exercises by name, path, topic, status or verified
+    sort: Option<SortKey>,
+    #[argh(option)]
+    /// group the table by topic
+    group_by: Option<GroupBy>,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct ExerciseCheckList {
     pub exercises: Vec<ExerciseResult>,
     pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
+    pub statistics: ExerciseStatistics,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct ExerciseResult {
     pub name: String,
-    pub result: bool
+    pub result: bool,
+    // The errors of the compiler, for exercises that don't compile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    // The seed of the failing property test, to rerun it with the same inputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
+pub struct ExerciseStatistics {
     pub total_exercations: usize,
     pub total_succeeds: usize,
     pub total_failures: usize,
@@ -152,6 +270,12 @@ pub struct  ExerciseStatistics {
 #[tokio::main]
 async fn main() {
     let args: Args = argh::from_env();
+    if let Some(lang) = &args.lang {
+        i18n::set_lang(lang);
+    } else if let Some(Subcommands::CicvVerify(_)) = args.nested {
+        // The classroom grades in Chinese, unless the locale has a language
+        i18n::set_default_lang("zh");
+    }
 
     if args.version {
         println!("v{VERSION}");
@@ -162,284 +286,481 @@ async fn main() {
         println!("\n{WELCOME}\n");
     }
 
+    // The paths of the exercises are relative to the root of the checkout,
+    // which may be above the current directory
+    let root = match env::var_os(course::ROOT_VAR) {
+        Some(root) => Some(PathBuf::from(root)),
+        None => env::current_dir()
+            .ok()
+            .and_then(|dir| course::find_root(&dir, args.course.is_some())),
+    };
+    if let Some(root) = root {
+        if let Err(e) = env::set_current_dir(&root) {
+            println!(
+                "Failed to open the rustlings directory {}: {e}",
+                root.display()
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let config = Config::load().unwrap_or_else(|e| {
+        println!("Failed to read {}: {e}", course::CONFIG_FILE);
+        std::process::exit(1);
+    });
+    let course = match &config {
+        Some(config) => config.select(args.course.as_deref()).unwrap_or_else(|e| {
+            println!("{e}");
+            std::process::exit(1);
+        }),
+        None if args.course.is_some() => {
+            println!(
+                "--course needs a {} listing the courses",
+                course::CONFIG_FILE
+            );
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    if let Some(Subcommands::Courses(_)) = args.nested {
+        match &config {
+            Some(config) => course::list(config, course.map(|(name, _)| name)),
+            None => println!("There is no {} listing courses here", course::CONFIG_FILE),
+        }
+        std::process::exit(0);
+    }
+    // The paths of the exercises of a course are relative to its root
+    if let Some((name, course)) = course {
+        if let Err(e) = env::set_current_dir(&course.root) {
+            println!(
+                "Failed to open the course {name} in {}: {e}",
+                course.root.display()
+            );
+            std::process::exit(1);
+        }
+    }
+
     if !Path::new("info.toml").exists() {
         println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
+            "{}",
+            tr!(
+                "main.wrong_dir",
+                exe = std::env::current_exe().unwrap().display()
+            )
         );
-        println!("Try `cd rustlings/`!");
+        println!("{}", tr!("main.try_cd", var = course::ROOT_VAR));
         std::process::exit(1);
     }
 
-    if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+    let exercise_list = ExerciseList::load("info.toml").unwrap_or_else(|e| {
+        println!("Failed to read info.toml: {e}");
         std::process::exit(1);
+    });
+    let min_rustc_version = exercise_list.min_rustc_version.as_deref().map(|version| {
+        Version::parse(version).unwrap_or_else(|| {
+            println!("min_rustc_version in info.toml isn't a valid version: {version}");
+            std::process::exit(1);
+        })
+    });
+    let exercises = exercise_list.exercises;
+
+    if let Some(Subcommands::Doctor(_)) = args.nested {
+        let ready = doctor(min_rustc_version);
+        std::process::exit(if ready { 0 } else { 1 });
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    if let Err(message) = check_rustc(min_rustc_version) {
+        println!("{message}");
+        println!("{}", tr!("doctor.run_doctor"));
+        std::process::exit(1);
+    }
     let verbose = args.nocapture;
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", tr!("main.default_out"));
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
-            }
-            let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
+            let selected: Vec<&Exercise> = match &subargs.filter {
+                Some(filter) => select_exercises(filter, &exercises),
+                None => exercises.iter().collect(),
+            };
+            let selected: Vec<&Exercise> = selected
+                .into_iter()
+                .filter(|e| {
                     (e.looks_done() && subargs.solved)
                         || (!e.looks_done() && subargs.unsolved)
                         || (!subargs.solved && !subargs.unsolved)
-                };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
-                }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
+                })
+                .collect();
+            let options = ListOptions {
+                format: subargs.format,
+                sort: subargs.sort,
+                group_by: subargs.group_by,
+                paths: subargs.paths,
+                names: subargs.names,
+            };
+            list::list(&selected, &options);
+            if options.format == Format::Table {
+                let exercises_done = exercises.iter().filter(|e| e.looks_done()).count();
+                let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+                println!(
+                    "Progress: You completed {} / {} exercises ({:.1} %).",
+                    exercises_done,
+                    exercises.len(),
+                    percentage_progress
+                );
+            }
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            // Every exercise is run, even after one fails
+            let failed = select_exercises(&subargs.name, &exercises)
+                .into_iter()
+                .filter(|exercise| run(exercise, verbose).is_err())
+                .count();
+            if failed > 0 {
+                std::process::exit(1);
+            }
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            // Like `run`, every exercise is reset, even after one fails
+            let failed = select_exercises(&subargs.name, &exercises)
+                .into_iter()
+                .filter(|exercise| {
+                    let failed = reset(exercise).is_err();
+                    if failed {
+                        warn!("{}", tr!("run.reset_failed", exercise = exercise));
+                    } else {
+                        success!("{}", tr!("run.reset", exercise = exercise));
+                    }
+                    failed
+                })
+                .count();
+            if failed > 0 {
+                std::process::exit(1);
+            }
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-
-            println!("{}", exercise.hint);
+            let selected = select_exercises(&subargs.name, &exercises);
+            for (i, exercise) in selected.iter().enumerate() {
+                // The hints of several exercises are told apart by their names
+                if selected.len() > 1 {
+                    println!("{}{}:", if i == 0 { "" } else { "\n" }, exercise.name);
+                }
+                println!("{}", exercise.hint);
+                if let Some(readme) = exercise.readme() {
+                    println!("\n{}", tr!("main.readme", path = readme.display()));
+                }
+            }
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Verify(subargs) => {
+            let selected = match &subargs.selector {
+                Some(selector) => select_exercises(selector, &exercises),
+                None => exercises.iter().collect(),
+            };
+            verify(
+                selected.iter().copied(),
+                (0, selected.len()),
+                verbose,
+                false,
+            )
+            .unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::CicvVerify(_subargs) => {
             // let toml_str = &fs::read_to_string("info.toml").unwrap();
             // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let now_start = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
             let rights = Arc::new(Mutex::new(0));
             let alls = exercises.len();
+            // The property tests all get the same seed, which the report of
+            // a failed exercise records to check it with the same inputs again
+            let seed = env::var(bundled::SEED_VAR).unwrap_or_else(|_| {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64;
+                format!("{seed:#x}")
+            });
 
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
-                    }
-                }
-            ));
+            let exercise_check_list = Arc::new(Mutex::new(ExerciseCheckList {
+                exercises: vec![],
+                user_name: None,
+                statistics: ExerciseStatistics {
+                    total_exercations: alls,
+                    total_succeeds: 0,
+                    total_failures: 0,
+                    total_time: 0,
+                },
+            }));
+
+            // The exercises are checked concurrently, which would disturb
+            // the timings of the benchmarks, so they're run afterwards
+            let benchmarked: Vec<Exercise> = exercises
+                .iter()
+                .filter(|e| e.benchmark.is_some())
+                .cloned()
+                .collect();
+            benchmark::defer(true);
 
             let mut tasks = vec![];
-            for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            for mut exercise in exercises {
+                exercise.options.seed = Some(seed.clone());
+                let now_start = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
                 let inner_exercise = exercise;
                 let c_mutex = Arc::clone(&rights);
                 let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
+                let _verbose = verbose;
+                let t = tokio::task::spawn(async move {
                     match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
+                        // match verify(vec![&inner_exercise], (0, 1), true, true) {
                         Ok(_) => {
                             *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
+                            println!("{}", tr!("cicv.succeeded", exercise = inner_exercise.name));
+                            println!("{}", tr!("cicv.total", total = alls));
+                            println!("{}", tr!("cicv.correct", count = *c_mutex.lock().unwrap()));
+                            let now_end = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            println!("{}", tr!("cicv.elapsed", seconds = now_end - now_start));
+                            exercise_check_list_ref.lock().unwrap().exercises.push(
+                                ExerciseResult {
+                                    name: inner_exercise.name,
+                                    result: true,
+                                    diagnostics: vec![],
+                                    seed: None,
+                                },
+                            );
+                            exercise_check_list_ref
+                                .lock()
+                                .unwrap()
+                                .statistics
+                                .total_succeeds += 1;
+                        }
+                        Err(output) => {
+                            println!("{}", tr!("cicv.failed", exercise = inner_exercise.name));
+                            println!("{}", tr!("cicv.total", total = alls));
+                            println!("{}", tr!("cicv.correct", count = *c_mutex.lock().unwrap()));
+                            let now_end = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            println!("{}", tr!("cicv.elapsed", seconds = now_end - now_start));
+                            let seed = output.failing_seed();
+                            let diagnostics = output
+                                .diagnostics
+                                .into_iter()
+                                .filter(|d| d.is_error() && !d.is_noise())
+                                .collect();
+                            exercise_check_list_ref.lock().unwrap().exercises.push(
+                                ExerciseResult {
+                                    name: inner_exercise.name,
+                                    result: false,
+                                    diagnostics,
+                                    seed,
+                                },
+                            );
+                            exercise_check_list_ref
+                                .lock()
+                                .unwrap()
+                                .statistics
+                                .total_failures += 1;
                         }
                     }
                 });
                 tasks.push(t);
             }
-            for task in tasks { task.await.unwrap(); }
-            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            for task in tasks {
+                task.await.unwrap();
+            }
+
+            benchmark::defer(false);
+            {
+                let mut check_list = exercise_check_list.lock().unwrap();
+                let ExerciseCheckList {
+                    exercises: results,
+                    statistics,
+                    ..
+                } = &mut *check_list;
+                for exercise in &benchmarked {
+                    let Some(result) = results
+                        .iter_mut()
+                        .find(|r| r.name == exercise.name && r.result)
+                    else {
+                        continue;
+                    };
+                    let Some(benchmark) = &exercise.benchmark else {
+                        continue;
+                    };
+                    match benchmark::run(exercise, benchmark) {
+                        Ok(report) => println!("{report}"),
+                        Err(report) => {
+                            println!("{report}");
+                            println!("{}", tr!("cicv.failed", exercise = exercise.name));
+                            result.result = false;
+                            statistics.total_succeeds -= 1;
+                            statistics.total_failures += 1;
+                        }
+                    }
+                }
+            }
+            let now_end = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
             let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
+            println!("{}", tr!("cicv.finished", seconds = total_time));
             let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
+            exercise_check_list_ref
+                .lock()
+                .unwrap()
+                .statistics
+                .total_time = total_time as u32;
+            let serialized =
+                serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
             fs::write(".github/result/check_result.json", serialized).unwrap();
-        },
+        }
 
-        Subcommands::Lsp(_subargs) => {
+        Subcommands::Lsp(subargs) => {
             let mut project = RustAnalyzerProject::new();
+            if let Err(e) = project.get_sysroot_src() {
+                println!("{e}");
+                std::process::exit(1);
+            }
             project
-                .get_sysroot_src()
-                .expect("Couldn't find toolchain path, do you have `rustc` installed?");
-            project
-                .exercises_to_json()
+                .exercises_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
-            if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
-            } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
+            if subargs.check {
+                let stale = project.stale_files();
+                if stale.is_empty() {
+                    println!("{}", tr!("lsp.up_to_date"));
+                } else {
+                    for path in stale {
+                        println!("{}", tr!("lsp.out_of_date", path = path.display()));
+                    }
+                    println!("{}", tr!("lsp.generate_again"));
+                    std::process::exit(1);
+                }
+            } else if project.crates.is_empty() && project.linked_projects.is_empty() {
+                println!("{}", tr!("lsp.no_exercises"));
+            } else if let Err(e) = project.write_to_disk() {
+                println!("{}", tr!("lsp.write_failed", error = e));
             } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("{}", tr!("lsp.generated"));
+                println!("{}", tr!("lsp.restart"));
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+        Subcommands::Doctor(_) => unreachable!("handled before checking rustc"),
+        Subcommands::Courses(_) => unreachable!("handled before loading info.toml"),
+
+        Subcommands::Selftest(subargs) => {
+            let ok = if subargs.toolchain.is_empty() {
+                let selected: Vec<Exercise> = match &subargs.filter {
+                    Some(filter) => select_exercises(filter, &exercises)
+                        .into_iter()
+                        .cloned()
+                        .collect(),
+                    None => exercises,
+                };
+                selftest::selftest(&selected, subargs.json)
+            } else {
+                selftest::selftest_toolchains(&subargs.toolchain, subargs.filter.as_deref())
+            };
+            if !ok {
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
+        }
+
+        Subcommands::New(subargs) => {
+            match scaffold::new_exercise(
+                &subargs.exercise,
+                subargs.mode,
+                subargs.after.as_deref(),
+                &exercises,
+            ) {
+                Ok(changed) => {
+                    for path in changed {
+                        println!("Wrote {}", path.display());
+                    }
+                    success!("Added {} to info.toml", subargs.exercise);
+                }
+                Err(e) => {
+                    println!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Serve(subargs) => {
+            if !subargs.stdio {
+                println!("Only `rustlings serve --stdio` is supported for now.");
+                std::process::exit(1);
             }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+            if let Err(e) = server::serve(exercises) {
+                eprintln!("Error while serving requests: {e}");
+                std::process::exit(1);
             }
-        },
-    }
-}
+        }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
+        Subcommands::Watch(subargs) => {
+            let result = if subargs.tui {
+                tui::watch(&exercises, subargs.success_hints, subargs.poll)
+            } else {
+                watch(
+                    exercises,
+                    verbose,
+                    subargs.success_hints,
+                    subargs.focus,
+                    subargs.poll,
+                )
+            };
+            match result {
+                Err(e) => {
+                    println!("{}", tr!("watch.failed", error = format!("{e:?}")));
+                    println!("{}", tr!("watch.failed_cause"));
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} {} {emoji}",
+                        tr!("main.all_done"),
+                        emoji = Emoji("🎉", "★")
+                    );
+                    println!("\n{FENISH_LINE}\n\n{}\n", tr!("main.finish"));
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("{}", tr!("watch.enjoying"));
+                    println!("{}", tr!("watch.come_back"));
                 }
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
+    }
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
-    if name.eq("next") {
-        exercises
-            .iter()
-            .find(|e| !e.looks_done())
-            .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
-                std::process::exit(1)
-            })
-    } else {
-        exercises
-            .iter()
-            .find(|e| e.name == name)
-            .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
-                std::process::exit(1)
-            })
-    }
+// The exercises chosen by a selector, see select.rs
+fn select_exercises<'a>(selector: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+    selector
+        .parse::<Selector>()
+        .and_then(|selector| selector.select(exercises))
+        .unwrap_or_else(|e| {
+            println!("{e}");
+            std::process::exit(1)
+        })
 }
 
 enum WatchStatus {
@@ -448,9 +769,11 @@ enum WatchStatus {
 }
 
 fn watch(
-    exercises: &[Exercise],
+    mut exercises: Vec<Exercise>,
     verbose: bool,
     success_hints: bool,
+    focus: bool,
+    poll: bool,
 ) -> notify::Result<WatchStatus> {
     /* Clears the terminal with an ANSI escape code.
     Works in UNIX and newer Windows terminals. */
@@ -459,61 +782,149 @@ fn watch(
     }
 
     let (tx, rx) = channel();
+    let (focus_tx, focus_rx) = channel();
     let should_quit = Arc::new(AtomicBool::new(false));
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+    let _watcher = watch_exercises(poll, tx)?;
+    let info_toml = Path::new("info.toml").canonicalize()?;
 
     clear_screen();
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
+    // Names of the exercises the learner skipped during this session. They
+    // are left out when verifying the pending exercises, until they are
+    // focused again.
+    let mut skipped: HashSet<String> = HashSet::new();
+    let position = |exercises: &[Exercise], exercise: &Exercise| {
+        exercises
+            .iter()
+            .position(|e| std::ptr::eq(e, exercise))
+            .unwrap()
+    };
+    // Verify the changed exercises first, followed by the remaining pending
+    // exercises unless watch mode only follows the focused exercise.
+    // Returns the index of the first exercise which still needs work.
+    let verify_from = |exercises: &[Exercise], changed: &[usize], skipped: &HashSet<String>| {
+        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
+        let pending_exercises = exercises.iter().enumerate().filter(|&(i, e)| {
+            !focus && !changed.contains(&i) && !skipped.contains(&e.name) && !e.looks_done()
+        });
+        verify(
+            changed
+                .iter()
+                .map(|&i| &exercises[i])
+                .chain(pending_exercises.map(|(_, e)| e)),
+            (num_done, exercises.len()),
+            verbose,
+            success_hints,
+        )
+        .map_err(|exercise| position(exercises, exercise))
+    };
+
+    let mut focused = match verify(
         exercises.iter(),
         (0, exercises.len()),
         verbose,
         success_hints,
     ) {
         Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
+        Err(exercise) => position(&exercises, exercise),
     };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    let state = Arc::new(Mutex::new(WatchState {
+        exercises: exercises.clone(),
+        focused,
+    }));
+    spawn_watch_shell(&state, Arc::clone(&should_quit), focus_tx, verbose);
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
+        // The exercises to verify next, if a file was saved or the focus changed
+        let mut changed = Vec::new();
+        match rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(Ok(paths)) => {
+                for filepath in paths {
+                    if filepath == info_toml {
+                        match ExerciseList::load(&info_toml) {
+                            Ok(list) if !list.exercises.is_empty() => {
+                                let focused_name = &exercises[focused].name;
+                                focused = list
+                                    .exercises
                                     .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
+                                    .position(|e| &e.name == focused_name)
+                                    .unwrap_or(0);
+                                exercises = list.exercises;
+                                changed = vec![focused];
+                                let mut state = state.lock().unwrap();
+                                state.exercises = exercises.clone();
+                                state.focused = focused;
+                            }
+                            Ok(_) => {
+                                println!("{}", tr!("watch.no_exercises"))
+                            }
+                            Err(e) => println!("{}", tr!("watch.reload_failed", error = e)),
+                        }
+                    } else {
+                        for index in affected_exercises(&exercises, &filepath) {
+                            if !changed.contains(&index) {
+                                changed.push(index);
                             }
                         }
                     }
                 }
-                _ => {}
-            },
+            }
+            Ok(Err(errors)) => println!("{}", tr!("watch.error", error = format!("{errors:?}"))),
             Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+                // the timeout expired, just check the focus commands and
+                // the `should_quit` variable below then loop again
+            }
+            Err(e) => println!("{}", tr!("watch.error", error = format!("{e:?}"))),
+        }
+        while let Ok(command) = focus_rx.try_recv() {
+            match command {
+                FocusCommand::Goto(name) => match exercises.iter().position(|e| e.name == name) {
+                    Some(index) => changed = vec![index],
+                    None => println!("{}", tr!("watch.not_found", name = name)),
+                },
+                FocusCommand::Next => {
+                    if focused + 1 < exercises.len() {
+                        changed = vec![focused + 1];
+                    } else {
+                        println!("{}", tr!("watch.last", exercise = exercises[focused].name));
+                    }
+                }
+                FocusCommand::Skip => {
+                    skipped.insert(exercises[focused].name.clone());
+                    let next = exercises
+                        .iter()
+                        .enumerate()
+                        .skip(focused + 1)
+                        .chain(exercises.iter().enumerate().take(focused))
+                        .find(|&(_, e)| !skipped.contains(&e.name) && !e.looks_done());
+                    match next {
+                        Some((index, _)) => changed = vec![index],
+                        None => println!("{}", tr!("watch.nothing_to_skip")),
+                    }
+                }
+            }
+        }
+        if let Some(&first) = changed.first() {
+            // Focusing an exercise explicitly brings it back from being skipped
+            for &index in &changed {
+                skipped.remove(&exercises[index].name);
             }
-            Err(e) => println!("watch error: {e:?}"),
+            clear_screen();
+            match verify_from(&exercises, &changed, &skipped) {
+                Ok(_) if exercises.iter().all(|e| e.looks_done()) => {
+                    return Ok(WatchStatus::Finished)
+                }
+                Ok(_) => {
+                    focused = first;
+                    if focus {
+                        println!("{}", tr!("watch.done", exercise = exercises[focused].name));
+                    } else {
+                        println!("{}", tr!("watch.only_skipped"));
+                    }
+                }
+                Err(index) => focused = index,
+            }
+            state.lock().unwrap().focused = focused;
         }
         // Check if we need to exit
         if should_quit.load(Ordering::SeqCst) {
@@ -522,44 +933,6 @@ fn watch(
     }
 }
 
-fn rustc_exists() -> bool {
-    Command::new("rustc")
-        .args(&["--version"])
-        .stdout(Stdio::null())
-        .spawn()
-        .and_then(|mut child| child.wait())
-        .map(|status| status.success())
-        .unwrap_or(false)
-}
-
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like
-   autocompletion, run the command `rustlings lsp`.
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
-
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
 +--------------------------  ------------------------+
@@ -578,14 +951,7 @@ const FENISH_LINE: &str = r#"+--------------------------------------------------
          ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
        ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
        ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
-           ▒▒  ▒▒                      ▒▒  ▒▒
-
-We hope you enjoyed learning about the various aspects of Rust!
-If you noticed any issues, please don't hesitate to report them to our repo.
-You can also contribute your own exercises to help the greater community!
-
-Before reporting an issue or contributing, please read our guidelines:
-https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
+           ▒▒  ▒▒                      ▒▒  ▒▒"#;
 
 const WELCOME: &str = r#"       welcome to...
                  _   _ _
@@ -1,99 +1,420 @@
-use glob::glob;
+use crate::bundled::BundledCrate;
+use crate::exercise::{Exercise, Mode};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
-/// Contains the structure of resulting rust-project.json file
-/// and functions to build the data required to create the file
+const RUST_PROJECT_PATH: &str = "./rust-project.json";
+const VSCODE_SETTINGS_PATH: &str = "./.vscode/settings.json";
+// Where the manifests of the exercises built with cargo are written. They
+// can't live next to the exercises, which rustlings overwrites while compiling.
+const LINKED_PROJECTS_DIR: &str = "target/rust-analyzer";
+const FEATURE_REGEX: &str = r#"feature\s*=\s*"([^"]+)""#;
+
+// Contains the structure of resulting rust-project.json file
+// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
 pub struct RustAnalyzerProject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sysroot: Option<String>,
     sysroot_src: String,
     pub crates: Vec<Crate>,
+    // Cargo manifests for the exercises built with cargo, which
+    // rust-analyzer loads as projects of their own
+    #[serde(skip)]
+    pub linked_projects: Vec<LinkedProject>,
+    // Sources rustlings bundles which the crates are read from, and where
+    // they go
+    #[serde(skip)]
+    pub sources: Vec<(PathBuf, String)>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    display_name: String,
     root_module: String,
     edition: String,
-    deps: Vec<String>,
+    deps: Vec<Dep>,
     cfg: Vec<String>,
 }
 
+// A dependency of a crate on another one of rust-project.json
+#[derive(Serialize, Deserialize)]
+pub struct Dep {
+    // The index of the crate depended on
+    #[serde(rename = "crate")]
+    krate: usize,
+    name: String,
+}
+
+pub struct LinkedProject {
+    manifest_path: PathBuf,
+    manifest: String,
+    // The cargo configuration of the project, and where it goes
+    config: Option<(PathBuf, String)>,
+}
+
+impl LinkedProject {
+    fn files(&self) -> impl Iterator<Item = (&PathBuf, &String)> {
+        std::iter::once((&self.manifest_path, &self.manifest))
+            .chain(self.config.iter().map(|(path, config)| (path, config)))
+    }
+}
+
 impl RustAnalyzerProject {
     pub fn new() -> RustAnalyzerProject {
         RustAnalyzerProject {
+            sysroot: None,
             sysroot_src: String::new(),
             crates: Vec::new(),
+            linked_projects: Vec::new(),
+            sources: Vec::new(),
         }
     }
 
-    /// Write rust-project.json to disk
-    pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
-        std::fs::write(
-            "./rust-project.json",
-            serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
+    fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).expect("Failed to serialize to JSON")
+    }
+
+    // Write rust-project.json and the manifests of the linked projects to
+    // disk, and point VS Code's rust-analyzer at all of them
+    pub fn write_to_disk(&self) -> Result<(), Box<dyn Error>> {
+        fs::write(RUST_PROJECT_PATH, self.to_json())?;
+        for (path, contents) in self.linked_projects.iter().flat_map(LinkedProject::files) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, contents)?;
+        }
+        for (path, contents) in &self.sources {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, contents)?;
+        }
+        if !self.linked_projects.is_empty() {
+            self.write_vscode_settings()?;
+        }
+        Ok(())
+    }
+
+    // rust-analyzer only loads rust-project.json by itself, so the linked
+    // projects have to be listed in the `rust-analyzer.linkedProjects`
+    // setting, next to it
+    fn write_vscode_settings(&self) -> Result<(), Box<dyn Error>> {
+        let mut settings = match fs::read_to_string(VSCODE_SETTINGS_PATH) {
+            Ok(settings) => serde_json::from_str(&settings)?,
+            Err(_) => Value::Object(Default::default()),
+        };
+        let linked_projects = std::iter::once(RUST_PROJECT_PATH.to_string())
+            .chain(
+                self.linked_projects
+                    .iter()
+                    .map(|p| p.manifest_path.display().to_string()),
+            )
+            .map(|path| Value::from(path.trim_start_matches("./")))
+            .collect();
+        settings
+            .as_object_mut()
+            .ok_or("`.vscode/settings.json` doesn't hold an object")?
+            .insert(
+                "rust-analyzer.linkedProjects".to_string(),
+                Value::Array(linked_projects),
+            );
+        fs::create_dir_all(".vscode")?;
+        fs::write(
+            VSCODE_SETTINGS_PATH,
+            serde_json::to_string_pretty(&settings)?,
         )?;
         Ok(())
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        if let Some(ext) = path.extension() {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
+    // List the generated files which differ from the ones on disk
+    pub fn stale_files(&self) -> Vec<PathBuf> {
+        let mut stale = Vec::new();
+        if fs::read(RUST_PROJECT_PATH).ok() != Some(self.to_json()) {
+            stale.push(PathBuf::from(RUST_PROJECT_PATH));
+        }
+        for (path, contents) in self.linked_projects.iter().flat_map(LinkedProject::files) {
+            if fs::read_to_string(path).ok().as_ref() != Some(contents) {
+                stale.push(path.clone());
+            }
+        }
+        for (path, contents) in &self.sources {
+            if fs::read_to_string(path).ok().as_ref() != Some(contents) {
+                stale.push(path.clone());
             }
         }
+        stale
+    }
 
+    // Add the exercises in the order of info.toml. Those compiled with
+    // `rustc` become crates of rust-project.json, while those built with
+    // cargo get a manifest per directory, with their build script attached.
+    // Exercises using crates get the cargo project they are built in.
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+        let mut cargo_dirs: BTreeMap<PathBuf, Vec<&Exercise>> = BTreeMap::new();
+        for exercise in exercises {
+            match exercise.mode {
+                Mode::Compile | Mode::Test if exercise.uses_crates() => {
+                    // Their manifest depends on the bundled crates by path
+                    for krate in exercise.bundled_crates() {
+                        self.add_source(krate.source_path(), krate.source.to_string());
+                        self.add_source(krate.manifest_path(), krate.manifest());
+                    }
+                    self.linked_projects.push(LinkedProject {
+                        manifest_path: exercise.cargo_project_dir().join("Cargo.toml"),
+                        manifest: exercise.cargo_project_manifest()?,
+                        config: Some(exercise.cargo_project_config()?),
+                    });
+                }
+                Mode::Compile | Mode::Test => {
+                    let mut cfg = vec![
+                        // This allows rust_analyzer to work inside #[test] blocks
+                        "test".to_string(),
+                    ];
+                    let features = features(&[exercise])?
+                        .into_iter()
+                        .map(|feature| format!("feature=\"{feature}\""));
+                    for option in features.chain(exercise.options.cfg.iter().cloned()) {
+                        if !cfg.contains(&option) {
+                            cfg.push(option);
+                        }
+                    }
+                    let deps = exercise
+                        .bundled_crates()
+                        .into_iter()
+                        .map(|krate| self.bundled_dep(krate))
+                        .collect();
+                    self.crates.push(Crate {
+                        display_name: exercise.name.clone(),
+                        root_module: exercise.path.display().to_string(),
+                        edition: exercise.edition().to_string(),
+                        deps,
+                        cfg,
+                    });
+                }
+                Mode::Clippy | Mode::BuildScript => {
+                    let dir = exercise.path.parent().unwrap_or(Path::new("."));
+                    cargo_dirs
+                        .entry(dir.to_path_buf())
+                        .or_default()
+                        .push(exercise);
+                }
+            }
+        }
+        for (dir, exercises) in cargo_dirs {
+            self.linked_projects.push(linked_project(&dir, &exercises)?);
+        }
         Ok(())
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
+    // Add a bundled source to write, unless it's there already
+    fn add_source(&mut self, path: PathBuf, contents: String) {
+        if !self.sources.iter().any(|(source, _)| *source == path) {
+            self.sources.push((path, contents));
         }
-        Ok(())
     }
 
-    /// Use `rustc` to determine the default toolchain
-    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
-        // check if RUST_SRC_PATH is set
-        if let Ok(path) = env::var("RUST_SRC_PATH") {
-            self.sysroot_src = path;
-            return Ok(());
+    // The dependency on a crate bundled with rustlings, which is added the
+    // first time an exercise needs it
+    fn bundled_dep(&mut self, bundled: &BundledCrate) -> Dep {
+        let source_path = bundled.source_path();
+        let root_module = source_path.display().to_string();
+        let krate = match self
+            .crates
+            .iter()
+            .position(|c| c.root_module == root_module)
+        {
+            Some(krate) => krate,
+            None => {
+                self.crates.push(Crate {
+                    display_name: bundled.name.to_string(),
+                    root_module,
+                    edition: "2021".to_string(),
+                    deps: Vec::new(),
+                    cfg: Vec::new(),
+                });
+                self.add_source(source_path, bundled.source.to_string());
+                self.crates.len() - 1
+            }
+        };
+        Dep {
+            krate,
+            name: bundled.name.to_string(),
         }
+    }
 
-        let toolchain = Command::new("rustc")
+    // Use `rustc` to determine the default toolchain, and check that the
+    // sources of the standard library are installed in it
+    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
+        let output = Command::new("rustc")
             .arg("--print")
             .arg("sysroot")
-            .output()?
-            .stdout;
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "`rustc --print sysroot` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        // The path may contain spaces, only the line break is trimmed
+        let sysroot = String::from_utf8(output.stdout)?.trim_end().to_string();
+        if !Path::new(&sysroot).is_dir() {
+            return Err(
+                format!("The sysroot reported by rustc, `{sysroot}`, doesn't exist").into(),
+            );
+        }
+        println!("Determined toolchain: {}\n", &sysroot);
 
-        let toolchain = String::from_utf8_lossy(&toolchain);
-        let mut whitespace_iter = toolchain.split_whitespace();
+        // check if RUST_SRC_PATH is set
+        let sysroot_src = match env::var("RUST_SRC_PATH") {
+            Ok(path) => {
+                if !Path::new(&path).is_dir() {
+                    return Err(
+                        format!("RUST_SRC_PATH is set to `{path}`, which doesn't exist").into(),
+                    );
+                }
+                path
+            }
+            Err(_) => {
+                let path = rust_src_path(Path::new(&sysroot));
+                if !path.is_dir() {
+                    return Err(missing_rust_src(&sysroot).into());
+                }
+                path.to_string_lossy().to_string()
+            }
+        };
 
-        let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
+        self.sysroot = Some(sysroot);
+        self.sysroot_src = sysroot_src;
+        Ok(())
+    }
+}
 
-        println!("Determined toolchain: {}\n", &toolchain);
+// Explain how to install the sources of the standard library, which
+// rust-analyzer needs and rustup leaves out of minimal installations
+fn missing_rust_src(sysroot: &str) -> String {
+    format!(
+        "The sources of the standard library are missing from `{sysroot}`, rust-analyzer needs them.\n\
+         Install them with `{}`, or set RUST_SRC_PATH to where they are.",
+        rust_src_install_command(Path::new(sysroot))
+    )
+}
 
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
-            .join("lib")
-            .join("rustlib")
-            .join("src")
-            .join("rust")
-            .join("library")
-            .to_string_lossy())
-        .to_string();
-        Ok(())
+// The command installing the sources of the standard library in a toolchain
+pub fn rust_src_install_command(sysroot: &Path) -> String {
+    // Toolchains installed by rustup live in `<rustup home>/toolchains/<name>`
+    let toolchain = sysroot
+        .parent()
+        .filter(|parent| parent.file_name().is_some_and(|name| name == "toolchains"))
+        .and_then(|_| sysroot.file_name())
+        .map(|name| name.to_string_lossy());
+    match toolchain {
+        Some(toolchain) => format!("rustup component add rust-src --toolchain {toolchain}"),
+        None => "rustup component add rust-src".to_string(),
+    }
+}
+
+// Where the sources of the standard library are in a toolchain
+pub fn rust_src_path(sysroot: &Path) -> PathBuf {
+    sysroot
+        .join("lib")
+        .join("rustlib")
+        .join("src")
+        .join("rust")
+        .join("library")
+}
+
+// The features the exercises check for with `cfg(feature = "...")`, which
+// their build scripts may enable
+fn features(exercises: &[&Exercise]) -> Result<Vec<String>, Box<dyn Error>> {
+    let re = Regex::new(FEATURE_REGEX)?;
+    let mut features = Vec::new();
+    for exercise in exercises {
+        let source = fs::read_to_string(&exercise.path)?;
+        for captures in re.captures_iter(&source) {
+            if !features.contains(&captures[1].to_string()) {
+                features.push(captures[1].to_string());
+            }
+        }
+    }
+    Ok(features)
+}
+
+// A cargo package holding all the exercises of a directory as binaries
+fn linked_project(dir: &Path, exercises: &[&Exercise]) -> Result<LinkedProject, Box<dyn Error>> {
+    let name = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("exercises");
+    let manifest_dir = Path::new(LINKED_PROJECTS_DIR).join(name);
+    // The manifest is further down the tree than the exercises, so the paths
+    // in it go back up to the root first
+    let to_root: PathBuf = manifest_dir
+        .components()
+        .map(|_| Component::ParentDir)
+        .collect();
+    let from_manifest = |path: &Path| to_root.join(path).display().to_string();
+
+    // Each binary has the edition of its exercise, the package takes the first
+    let edition = exercises.first().map_or("2021", |e| e.edition());
+    let mut manifest =
+        format!("[package]\nname = \"{name}\"\nversion = \"0.0.1\"\nedition = \"{edition}\"\n");
+    let build_script = dir.join("build.rs");
+    if build_script.exists() {
+        manifest += &format!("build = \"{}\"\n", from_manifest(&build_script));
+    }
+    let features = features(exercises)?;
+    if !features.is_empty() {
+        manifest += "\n[features]\n";
+        for feature in features {
+            manifest += &format!("{feature} = []\n");
+        }
+    }
+    for exercise in exercises {
+        manifest += &format!(
+            "\n[[bin]]\nname = \"{}\"\npath = \"{}\"\nedition = \"{}\"\n",
+            exercise.name,
+            from_manifest(&exercise.path),
+            exercise.edition()
+        );
+    }
+    // cargo has the same flags for all the binaries, so they get the cfg and
+    // the flags of every exercise, like the features
+    let mut cfg: Vec<&String> = Vec::new();
+    let mut flags: Vec<&[String]> = Vec::new();
+    for exercise in exercises {
+        for option in &exercise.options.cfg {
+            if !cfg.contains(&option) {
+                cfg.push(option);
+            }
+        }
+        if !flags.contains(&&exercise.options.rustc_flags[..]) {
+            flags.push(&exercise.options.rustc_flags);
+        }
     }
+    let rustflags: Vec<String> = cfg
+        .into_iter()
+        .flat_map(|option| ["--cfg".to_string(), option.clone()])
+        .chain(flags.concat())
+        .collect();
+    let config = (!rustflags.is_empty()).then(|| {
+        (
+            manifest_dir.join(".cargo").join("config.toml"),
+            format!("[build]\nrustflags = {}\n", toml::Value::from(rustflags)),
+        )
+    });
+    Ok(LinkedProject {
+        manifest_path: manifest_dir.join("Cargo.toml"),
+        manifest,
+        config,
+    })
 }
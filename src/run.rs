@@ -1,6 +1,8 @@
 use std::process::Command;
 
-use crate::exercise::{Exercise, Mode};
+use crate::exercise::{Exercise, ExerciseOutput, Mode};
+use crate::explanations::explanations;
+use crate::history;
 use crate::verify::test;
 use indicatif::ProgressBar;
 
@@ -8,7 +10,8 @@ use indicatif::ProgressBar;
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+// On failure, the output of the compiler or of the run is returned.
+pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ExerciseOutput> {
     match exercise.mode {
         Mode::Test => test(exercise, verbose)?,
         Mode::Compile => compile_and_run(exercise)?,
@@ -19,24 +22,25 @@ pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
 }
 
 // Resets the exercise by stashing the changes.
+// The output of git is not shown, as the TUI and the server share this.
 pub fn reset(exercise: &Exercise) -> Result<(), ()> {
     let command = Command::new("git")
         .args(["stash", "--"])
         .arg(&exercise.path)
-        .spawn();
+        .output();
 
     match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
+        Ok(output) if output.status.success() => Ok(()),
+        _ => Err(()),
     }
 }
 
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+fn compile_and_run(exercise: &Exercise) -> Result<(), ExerciseOutput> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
+    progress_bar.set_message(tr!("verify.compiling", exercise = exercise));
     progress_bar.enable_steady_tick(100);
 
     let compilation_result = exercise.compile();
@@ -44,31 +48,35 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
         Ok(compilation) => compilation,
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compilation of {} failed!, Compiler error message:\n",
-                exercise
-            );
+            history::record(exercise, false);
+            warn!("{}\n", tr!("run.compilation_failed", exercise = exercise));
             println!("{}", output.stderr);
-            return Err(());
+            print!("{}", explanations(exercise, &output.diagnostics));
+            return Err(output);
         }
     };
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(tr!("verify.running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     match result {
         Ok(output) => {
+            history::record(exercise, true);
             println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
+            for report in &output.reports {
+                println!("{report}");
+            }
+            success!("{}", tr!("run.ran", exercise = exercise));
             Ok(())
         }
         Err(output) => {
+            history::record(exercise, false);
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
-            Err(())
+            warn!("{}", tr!("verify.ran_with_errors", exercise = exercise));
+            Err(output)
         }
     }
 }
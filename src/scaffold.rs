@@ -0,0 +1,327 @@
+use crate::exercise::{Exercise, Mode};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The directory of the chapters
+const EXERCISES_DIR: &str = "exercises";
+// The manifest the exercise is added to
+const INFO_TOML: &str = "info.toml";
+// The table mapping the chapters to the book, in the README of the exercises
+const CHAPTERS_README: &str = "exercises/README.md";
+
+// The exercise of a mode, as the learner first sees it
+fn template(name: &str, mode: Mode) -> String {
+    let header = format!(
+        "// {name}.rs
+//
+// TODO: describe what the learner has to do.
+//
+// Execute `rustlings hint {name}` or use the `hint` watch subcommand for a
+// hint.
+
+// I AM NOT DONE
+"
+    );
+    let body = match mode {
+        Mode::Test => {
+            "
+pub fn answer() -> i32 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        assert_eq!(answer(), 42);
+    }
+}
+"
+        }
+        Mode::Clippy => {
+            "
+// The exercise passes once `cargo clippy` has nothing left to say about it.
+
+fn main() {
+    let answer = 42;
+    println!(\"{}\", answer);
+}
+"
+        }
+        Mode::Compile | Mode::BuildScript => {
+            "
+fn main() {
+    // TODO: the code which doesn't compile yet
+}
+"
+        }
+    };
+    header + body
+}
+
+// The entry of the exercise in info.toml
+fn manifest_entry(name: &str, path: &Path, mode: Mode) -> String {
+    format!(
+        "[[exercises]]
+name = \"{name}\"
+path = \"{}\"
+mode = \"{}\"
+hint = \"\"\"
+TODO: a hint for {name}.\"\"\"
+",
+        path.display(),
+        mode.name()
+    )
+}
+
+// The README of a new chapter
+fn chapter_readme(topic: &str) -> String {
+    let mut title = topic.replace('_', " ");
+    if let Some(first) = title.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    format!(
+        "# {title}
+
+TODO: introduce {title}.
+
+## Further information
+
+- [The Rust Programming Language](https://doc.rust-lang.org/book/)
+"
+    )
+}
+
+// Whether `name` can be the name of a chapter or an exercise
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+// Add `entry` to the manifest right after the block of the exercise `after`,
+// or at the end under a new section named after the chapter if None
+fn insert_entry(
+    manifest: &str,
+    entry: &str,
+    after: Option<&str>,
+    topic: &str,
+) -> Result<String, String> {
+    let Some(after) = after else {
+        let mut manifest = manifest.trim_end().to_string();
+        manifest += &format!("\n\n# {}\n\n{entry}", topic.to_uppercase());
+        return Ok(manifest);
+    };
+
+    let lines: Vec<&str> = manifest.split_inclusive('\n').collect();
+    let name_line = format!("name = \"{after}\"");
+    let mut in_string = false;
+    let mut start = None;
+    let mut end = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        // The hints are multi-line strings, whose lines aren't keys
+        let was_in_string = in_string;
+        if line.matches("\"\"\"").count() % 2 == 1 {
+            in_string = !in_string;
+        }
+        if was_in_string {
+            continue;
+        }
+        if start.is_none() {
+            if trimmed == name_line {
+                start = Some(i);
+            }
+        } else if trimmed == "[[exercises]]" || trimmed.starts_with('#') {
+            end = i;
+            break;
+        }
+    }
+    if start.is_none() {
+        return Err(format!("There is no exercise named {after} in {INFO_TOML}"));
+    }
+    // The blank lines before the next block stay after the new one
+    while end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    let mut manifest = lines[..end].concat();
+    if !manifest.ends_with('\n') {
+        manifest.push('\n');
+    }
+    manifest += "\n";
+    manifest += entry;
+    manifest += &lines[end..].concat();
+    Ok(manifest)
+}
+
+// Add a row for a new chapter to the table of the README of the exercises
+fn add_chapter_row(readme: &str, topic: &str) -> String {
+    let lines: Vec<&str> = readme.split_inclusive('\n').collect();
+    let Some(last_row) = lines.iter().rposition(|line| line.starts_with('|')) else {
+        return readme.to_string();
+    };
+    let mut readme = lines[..=last_row].concat();
+    if !readme.ends_with('\n') {
+        readme.push('\n');
+    }
+    readme += &format!("| {topic:<22} | {:<19} |\n", "n/a");
+    readme += &lines[last_row + 1..].concat();
+    readme
+}
+
+// Create the exercise `topic/name` of the mode, and add it to info.toml after
+// the exercise `after`, or else after the last exercise of its chapter. The
+// chapter's directory and README are created if it's a new one. Returns the
+// files created or changed.
+pub fn new_exercise(
+    exercise: &str,
+    mode: Mode,
+    after: Option<&str>,
+    exercises: &[Exercise],
+) -> Result<Vec<PathBuf>, String> {
+    let (topic, name) = exercise
+        .split_once('/')
+        .ok_or_else(|| format!("Expected <topic>/<name>, like tests/tests10, not {exercise}"))?;
+    if !is_valid_name(topic) || !is_valid_name(name) {
+        return Err(format!(
+            "The topic and name of {exercise} can only use lowercase letters, digits and `_`"
+        ));
+    }
+    if mode == Mode::BuildScript {
+        return Err(
+            "Build script exercises need a cargo project of their own, add them by hand"
+                .to_string(),
+        );
+    }
+    // They're all linted with the one manifest of exercises/clippy
+    if mode == Mode::Clippy && topic != "clippy" {
+        return Err(format!(
+            "Clippy exercises can only be added to the clippy chapter, like clippy/{name}"
+        ));
+    }
+    if exercises.iter().any(|e| e.name == name) {
+        return Err(format!("There is already an exercise named {name}"));
+    }
+
+    let chapter = Path::new(EXERCISES_DIR).join(topic);
+    let path = chapter.join(format!("{name}.rs"));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    // By default, the exercise goes last in its chapter
+    let after = after.map(str::to_string).or_else(|| {
+        exercises
+            .iter()
+            .rfind(|e| e.path.parent() == Some(chapter.as_path()))
+            .map(|e| e.name.clone())
+    });
+
+    let manifest = fs::read_to_string(INFO_TOML).map_err(|e| e.to_string())?;
+    let manifest = insert_entry(
+        &manifest,
+        &manifest_entry(name, &path, mode),
+        after.as_deref(),
+        topic,
+    )?;
+
+    let mut changed = Vec::new();
+    let error = |e: std::io::Error| format!("Failed to create {exercise}: {e}");
+    if !chapter.exists() {
+        fs::create_dir_all(&chapter).map_err(error)?;
+        let readme = chapter.join("README.md");
+        fs::write(&readme, chapter_readme(topic)).map_err(error)?;
+        changed.push(readme);
+        if let Ok(chapters) = fs::read_to_string(CHAPTERS_README) {
+            fs::write(CHAPTERS_README, add_chapter_row(&chapters, topic)).map_err(error)?;
+            changed.push(PathBuf::from(CHAPTERS_README));
+        }
+    }
+    fs::write(&path, template(name, mode)).map_err(error)?;
+    changed.push(path);
+    fs::write(INFO_TOML, manifest).map_err(error)?;
+    changed.push(PathBuf::from(INFO_TOML));
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MANIFEST: &str = r#"# TESTS
+
+[[exercises]]
+name = "tests1"
+path = "exercises/tests/tests1.rs"
+mode = "test"
+hint = """
+# Not a section
+[[exercises]]"""
+
+[[exercises]]
+name = "tests2"
+path = "exercises/tests/tests2.rs"
+mode = "test"
+hint = """"""
+
+# THREADS
+
+[[exercises]]
+name = "threads1"
+path = "exercises/threads/threads1.rs"
+mode = "compile"
+hint = """"""
+"#;
+
+    const ENTRY: &str = "[[exercises]]\nname = \"new1\"\n";
+
+    #[test]
+    fn test_insert_entry_after_exercise() {
+        let manifest = insert_entry(MANIFEST, ENTRY, Some("tests1"), "tests").unwrap();
+        assert!(manifest.contains("[[exercises]]\"\"\"\n\n[[exercises]]\nname = \"new1\"\n\n[[exercises]]\nname = \"tests2\""));
+    }
+
+    #[test]
+    fn test_insert_entry_at_end_of_section() {
+        let manifest = insert_entry(MANIFEST, ENTRY, Some("tests2"), "tests").unwrap();
+        assert!(
+            manifest.contains("hint = \"\"\"\"\"\"\n\n[[exercises]]\nname = \"new1\"\n\n# THREADS")
+        );
+    }
+
+    #[test]
+    fn test_insert_entry_at_end_of_file() {
+        let manifest = insert_entry(MANIFEST, ENTRY, Some("threads1"), "threads").unwrap();
+        assert!(manifest.ends_with("hint = \"\"\"\"\"\"\n\n[[exercises]]\nname = \"new1\"\n"));
+    }
+
+    #[test]
+    fn test_insert_entry_in_new_section() {
+        let manifest = insert_entry(MANIFEST, ENTRY, None, "async").unwrap();
+        assert!(manifest
+            .ends_with("hint = \"\"\"\"\"\"\n\n# ASYNC\n\n[[exercises]]\nname = \"new1\"\n"));
+    }
+
+    #[test]
+    fn test_insert_entry_after_unknown_exercise() {
+        assert!(insert_entry(MANIFEST, ENTRY, Some("tests3"), "tests").is_err());
+    }
+
+    #[test]
+    fn test_clippy_exercises_stay_in_their_chapter() {
+        let error = new_exercise("tests/lints1", Mode::Clippy, None, &[]).unwrap_err();
+        assert!(error.contains("clippy/lints1"));
+    }
+
+    #[test]
+    fn test_add_chapter_row() {
+        let readme = "# Chapters\n\n| Exercise | Book |\n| -------- | ---- |\n| tests    | §11  |\n\nMore text\n";
+        let readme = add_chapter_row(readme, "async");
+        assert!(readme.contains(
+            "| tests    | §11  |\n| async                  | n/a                 |\n\nMore text\n"
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::exercise::Exercise;
+use crate::history::{self, Record};
+use glob::Pattern;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+// How many names "did you mean" suggests at most
+const SUGGESTIONS: usize = 3;
+
+// A part of a selector, between its commas
+#[derive(Debug)]
+enum Term {
+    // The first exercise not done yet
+    Next,
+    // The exercise checked most recently, or else the next one
+    Current,
+    // The exercises whose last check failed
+    Failed,
+    // An exercise by its name, or the exercises of a topic
+    Name(String),
+    // The exercises whose name or path matches, like `/^iter.*[0-9]$/`
+    Regex(Regex),
+    // The exercises whose name or path matches, like `algorithm*`
+    Glob(Pattern),
+}
+
+impl FromStr for Term {
+    type Err = String;
+
+    fn from_str(term: &str) -> Result<Self, Self::Err> {
+        Ok(match term {
+            "next" => Term::Next,
+            "current" => Term::Current,
+            "failed" => Term::Failed,
+            _ if term.len() > 1 && term.starts_with('/') && term.ends_with('/') => {
+                Regex::new(&term[1..term.len() - 1])
+                    .map(Term::Regex)
+                    .map_err(|e| format!("invalid regular expression `{term}`: {e}"))?
+            }
+            _ if term.contains(['*', '?', '[']) => Pattern::new(term)
+                .map(Term::Glob)
+                .map_err(|e| format!("invalid pattern `{term}`: {e}"))?,
+            _ => Term::Name(term.to_string()),
+        })
+    }
+}
+
+// The exercises chosen on the command line of `run`, `hint`, `reset`,
+// `verify` and `list`, as comma separated terms. An exercise matching any of
+// the terms is selected, and every term has to match at least one.
+#[derive(Debug)]
+pub struct Selector {
+    terms: Vec<(String, Term)>,
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        let terms = selector
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| Ok((term.to_string(), term.parse()?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        if terms.is_empty() {
+            return Err(String::from("no exercise selected"));
+        }
+        Ok(Selector { terms })
+    }
+}
+
+impl Selector {
+    // The selected exercises, in the order of info.toml
+    pub fn select<'a>(&self, exercises: &'a [Exercise]) -> Result<Vec<&'a Exercise>, String> {
+        let needs_history = self
+            .terms
+            .iter()
+            .any(|(_, term)| matches!(term, Term::Current | Term::Failed));
+        let history = if needs_history {
+            history::load()
+        } else {
+            BTreeMap::new()
+        };
+        self.select_with(exercises, &history)
+    }
+
+    fn select_with<'a>(
+        &self,
+        exercises: &'a [Exercise],
+        history: &BTreeMap<String, Record>,
+    ) -> Result<Vec<&'a Exercise>, String> {
+        let mut selected = vec![false; exercises.len()];
+        for (text, term) in &self.terms {
+            let matches = matching(term, exercises, history);
+            if matches.is_empty() {
+                return Err(not_found(text, term, exercises));
+            }
+            matches.into_iter().for_each(|index| selected[index] = true);
+        }
+        Ok(exercises
+            .iter()
+            .zip(selected)
+            .filter_map(|(exercise, selected)| selected.then_some(exercise))
+            .collect())
+    }
+}
+
+// The indices of the exercises matching a term
+fn matching(term: &Term, exercises: &[Exercise], history: &BTreeMap<String, Record>) -> Vec<usize> {
+    let next = || exercises.iter().position(|e| !e.looks_done());
+    let indices = |matches: &dyn Fn(&Exercise) -> bool| {
+        exercises
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| matches(e))
+            .map(|(i, _)| i)
+            .collect()
+    };
+    match term {
+        Term::Next => next().into_iter().collect(),
+        Term::Current => exercises
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| Some((history.get(&e.name)?.checked, i)))
+            .max()
+            .map(|(_, i)| i)
+            .or_else(next)
+            .into_iter()
+            .collect(),
+        Term::Failed => indices(&|e| {
+            history
+                .get(&e.name)
+                .is_some_and(|record| record.last_failed)
+        }),
+        Term::Name(name) => match exercises.iter().position(|e| &e.name == name) {
+            Some(index) => vec![index],
+            None => indices(&|e| e.topic() == Some(name.as_str())),
+        },
+        Term::Regex(regex) => {
+            indices(&|e| regex.is_match(&e.name) || regex.is_match(&e.path.to_string_lossy()))
+        }
+        Term::Glob(pattern) => {
+            indices(&|e| pattern.matches(&e.name) || pattern.matches_path(&e.path))
+        }
+    }
+}
+
+// Why a term matches no exercise, with the names it may be a typo of
+fn not_found(text: &str, term: &Term, exercises: &[Exercise]) -> String {
+    match term {
+        Term::Next | Term::Current => String::from(
+            "🎉 Congratulations! You have done all the exercises!\n\
+             🔚 There are no more exercises to do next!",
+        ),
+        Term::Failed => String::from("No exercise failed its last check!"),
+        _ => {
+            let mut message = format!("No exercise found for '{text}'!");
+            let candidates = exercises
+                .iter()
+                .map(|e| e.name.as_str())
+                .chain(exercises.iter().filter_map(Exercise::topic));
+            let suggestions = suggestions(text, candidates);
+            if !suggestions.is_empty() {
+                message += &format!("\nDid you mean {}?", suggestions.join(", "));
+            }
+            message
+        }
+    }
+}
+
+// The candidates closest to a misspelled name, the closest first
+fn suggestions<'a>(text: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let text = text.to_lowercase();
+    let max_distance = (text.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &str)> = candidates
+        .map(|candidate| (distance(&text, &candidate.to_lowercase()), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    close.sort();
+    close.dedup_by_key(|&mut (_, candidate)| candidate);
+    close
+        .into_iter()
+        .take(SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+// The Levenshtein distance between two strings: how many characters have to
+// be inserted, removed or replaced to turn one into the other
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &b) in b.iter().enumerate() {
+            let replaced = previous[j] + usize::from(a != b);
+            current.push(replaced.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise::for_test(path, Mode::Compile)
+    }
+
+    fn names(
+        selector: &str,
+        exercises: &[Exercise],
+        history: &BTreeMap<String, Record>,
+    ) -> Vec<String> {
+        let selector: Selector = selector.parse().unwrap();
+        selector
+            .select_with(exercises, history)
+            .unwrap()
+            .iter()
+            .map(|e| e.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let exercises = [
+            exercise("exercises/variables/variables1.rs"),
+            exercise("exercises/variables/variables2.rs"),
+            exercise("exercises/algorithm/algorithm1.rs"),
+            exercise("exercises/algorithm/algorithm10.rs"),
+        ];
+        let history = BTreeMap::new();
+        assert_eq!(names("variables2", &exercises, &history), ["variables2"]);
+        assert_eq!(
+            names("variables", &exercises, &history),
+            ["variables1", "variables2"]
+        );
+        assert_eq!(names("algorithm?", &exercises, &history), ["algorithm1"]);
+        assert_eq!(
+            names("exercises/algorithm/*", &exercises, &history),
+            ["algorithm1", "algorithm10"]
+        );
+        assert_eq!(
+            names("/1[0-9]*$/", &exercises, &history),
+            ["variables1", "algorithm1", "algorithm10"]
+        );
+        // In the order of info.toml, once each
+        assert_eq!(
+            names("algorithm1, variables1,variables*", &exercises, &history),
+            ["variables1", "variables2", "algorithm1"]
+        );
+    }
+
+    #[test]
+    fn test_select_from_history() {
+        let exercises = [
+            exercise("exercises/intro/intro1.rs"),
+            exercise("exercises/intro/intro2.rs"),
+            exercise("exercises/intro/intro3.rs"),
+        ];
+        let record = |passed, checked, last_failed| Record {
+            passed,
+            checked,
+            last_failed,
+        };
+        let history = BTreeMap::from([
+            (String::from("intro1"), record(Some(3), 3, false)),
+            (String::from("intro2"), record(None, 5, true)),
+            (String::from("intro3"), record(Some(2), 4, true)),
+        ]);
+        assert_eq!(names("failed", &exercises, &history), ["intro2", "intro3"]);
+        assert_eq!(names("current", &exercises, &history), ["intro2"]);
+    }
+
+    #[test]
+    fn test_not_found() {
+        let exercises = [
+            exercise("exercises/variables/variables1.rs"),
+            exercise("exercises/variables/variables2.rs"),
+            exercise("exercises/intro/intro1.rs"),
+        ];
+        let selector: Selector = "varables1".parse().unwrap();
+        assert_eq!(
+            selector
+                .select_with(&exercises, &BTreeMap::new())
+                .unwrap_err(),
+            "No exercise found for 'varables1'!\nDid you mean variables1, variables, variables2?"
+        );
+        let selector: Selector = "intro1,nothing*".parse().unwrap();
+        assert_eq!(
+            selector
+                .select_with(&exercises, &BTreeMap::new())
+                .unwrap_err(),
+            "No exercise found for 'nothing*'!"
+        );
+        assert!("/(/".parse::<Selector>().is_err());
+        assert!(" , ".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("intro1", "intro1"), 0);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::exercise::{Exercise, ExerciseOutput};
+use console::{strip_ansi_codes, style};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The directory of the reference solutions, which mirrors the paths of the
+// exercises: the solution of `exercises/tests/tests1.rs` is
+// `solutions/tests/tests1.rs`
+const SOLUTIONS_DIR: &str = "solutions";
+// How many lines of the output of a failure are shown
+const OUTPUT_LINES: usize = 20;
+
+// The outcome of the self-test of an exercise
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "outcome", content = "reason", rename_all = "lowercase")]
+pub enum Outcome {
+    // The unsolved exercise fails, and its solution passes
+    Passed,
+    // The exercise has no solution to apply
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SelftestResult {
+    pub name: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+// The path of the reference solution of an exercise
+fn solution_path(exercise: &Exercise) -> PathBuf {
+    let path = exercise
+        .path
+        .strip_prefix("exercises")
+        .unwrap_or(&exercise.path);
+    Path::new(SOLUTIONS_DIR).join(path)
+}
+
+// The start of the output of an exercise, without colors
+fn summary(output: &ExerciseOutput) -> String {
+    let text = format!("{}\n{}", output.stderr, output.stdout);
+    let text = strip_ansi_codes(text.trim());
+    let lines: Vec<&str> = text.lines().collect();
+    let mut summary = lines[..lines.len().min(OUTPUT_LINES)].join("\n");
+    if lines.len() > OUTPUT_LINES {
+        summary += &format!("\n... ({} more lines)", lines.len() - OUTPUT_LINES);
+    }
+    summary
+}
+
+// Compile and run the exercise, as `rustlings run` does
+fn passes(exercise: &Exercise) -> Result<(), String> {
+    let compiled = exercise.compile().map_err(|output| summary(&output))?;
+    compiled
+        .run()
+        .map(|_| ())
+        .map_err(|output| summary(&output))
+}
+
+// Check that the shipped exercise fails, or still has the `I AM NOT DONE`
+// marker, and that its solution passes. The solution is built from where it
+// is, so that the exercise of the learner is never touched.
+fn selftest_exercise(exercise: &Exercise) -> Outcome {
+    let solution_path = solution_path(exercise);
+    if !solution_path.is_file() {
+        return Outcome::Skipped(format!("there is no {}", solution_path.display()));
+    }
+    if exercise.looks_done() && passes(exercise).is_ok() {
+        return Outcome::Failed(
+            "the unsolved exercise already passes, and has no `I AM NOT DONE` marker".to_string(),
+        );
+    }
+
+    let solution = Exercise {
+        path: solution_path,
+        ..exercise.clone()
+    };
+    match passes(&solution) {
+        Ok(()) => Outcome::Passed,
+        Err(output) => Outcome::Failed(format!("the solution doesn't pass:\n{output}")),
+    }
+}
+
+// Self-test the exercises with the rustc of the environment
+fn selftest_exercises(exercises: &[Exercise], progress: bool) -> Vec<SelftestResult> {
+    exercises
+        .iter()
+        .enumerate()
+        .map(|(i, exercise)| {
+            if progress {
+                println!("[{}/{}] {exercise}", i + 1, exercises.len());
+            }
+            SelftestResult {
+                name: exercise.name.clone(),
+                outcome: selftest_exercise(exercise),
+            }
+        })
+        .collect()
+}
+
+// Self-test the exercises with another toolchain, by running rustlings again
+// with it selected through rustup
+fn selftest_with_toolchain(
+    toolchain: &str,
+    filter: Option<&str>,
+) -> Result<Vec<SelftestResult>, String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let mut command = Command::new(exe);
+    command.args(["selftest", "--json"]);
+    if let Some(filter) = filter {
+        command.args(["--filter", filter]);
+    }
+    let output = command
+        .env("RUSTUP_TOOLCHAIN", toolchain)
+        .output()
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&output.stdout).map_err(|_| {
+        format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .trim()
+        .to_string()
+    })
+}
+
+// Whether no exercise failed, and at least one was tested: without the
+// solutions, every exercise is skipped
+fn succeeded(results: &[SelftestResult]) -> bool {
+    results.iter().any(|r| r.outcome == Outcome::Passed)
+        && !results
+            .iter()
+            .any(|r| matches!(r.outcome, Outcome::Failed(_)))
+}
+
+fn print_results(results: &[SelftestResult]) -> bool {
+    for result in results {
+        match &result.outcome {
+            Outcome::Passed => println!("{} {}", style("✓").green(), result.name),
+            Outcome::Skipped(reason) => {
+                println!("{} {}: {reason}", style("-").yellow(), result.name)
+            }
+            Outcome::Failed(reason) => {
+                println!("{} {}: {reason}", style("✗").red(), result.name);
+            }
+        }
+    }
+    let count =
+        |passed: fn(&Outcome) -> bool| results.iter().filter(|r| passed(&r.outcome)).count();
+    println!(
+        "{} passed, {} failed, {} skipped",
+        count(|o| *o == Outcome::Passed),
+        count(|o| matches!(o, Outcome::Failed(_))),
+        count(|o| matches!(o, Outcome::Skipped(_)))
+    );
+    if results
+        .iter()
+        .all(|r| matches!(r.outcome, Outcome::Skipped(_)))
+    {
+        println!(
+            "{} No exercise was tested, their solutions go in {SOLUTIONS_DIR}/",
+            style("✗").red()
+        );
+    }
+    succeeded(results)
+}
+
+// Self-test the exercises with every toolchain, and report the exercises
+// which pass with the first one but not with a later one. Returns whether
+// no exercise failed and some were tested, with every toolchain.
+pub fn selftest_toolchains(toolchains: &[String], filter: Option<&str>) -> bool {
+    let mut ok = true;
+    let mut baseline: Option<(&str, Vec<SelftestResult>)> = None;
+    let mut regressions = Vec::new();
+    for toolchain in toolchains {
+        println!("\nSelf-test with {toolchain}:");
+        let results = match selftest_with_toolchain(toolchain, filter) {
+            Ok(results) => results,
+            Err(output) => {
+                ok = false;
+                println!(
+                    "{} Failed to run with {toolchain}:\n{output}",
+                    style("✗").red()
+                );
+                continue;
+            }
+        };
+        ok &= print_results(&results);
+        match &baseline {
+            None => baseline = Some((toolchain, results)),
+            Some((_, passing)) => {
+                for result in &results {
+                    let passed_before = passing
+                        .iter()
+                        .any(|r| r.name == result.name && r.outcome == Outcome::Passed);
+                    if passed_before && matches!(result.outcome, Outcome::Failed(_)) {
+                        regressions.push(format!("{} with {toolchain}", result.name));
+                    }
+                }
+            }
+        }
+    }
+    if let Some((first, _)) = baseline {
+        if !regressions.is_empty() {
+            println!("\nRegressions from {first}:");
+            for regression in regressions {
+                println!("  {regression}");
+            }
+        }
+    }
+    ok
+}
+
+// Self-test the exercises with the rustc of the environment and print the
+// results, as text or JSON. Returns whether no exercise failed and some
+// were tested.
+pub fn selftest(exercises: &[Exercise], json: bool) -> bool {
+    let results = selftest_exercises(exercises, !json);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+        succeeded(&results)
+    } else {
+        println!();
+        print_results(&results)
+    }
+}
@@ -0,0 +1,285 @@
+use crate::exercise::{affected_exercises, Exercise, State};
+use crate::history;
+use crate::run::reset;
+use crate::watcher::{watch_exercises, ChangedFiles};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// The error codes defined by JSON-RPC 2.0
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+const METHODS: &[&str] = &[
+    "initialize",
+    "exercises/list",
+    "exercise/state",
+    "exercise/run",
+    "exercise/hint",
+    "exercise/reset",
+    "progress/subscribe",
+    "shutdown",
+    "exit",
+];
+
+#[derive(Deserialize)]
+struct Request {
+    // Missing for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> RpcError {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// Writes messages to stdout with the framing of the Language Server Protocol,
+// so that the JSON-RPC clients of editors can be used as they are
+#[derive(Clone)]
+struct Output(Arc<Mutex<io::Stdout>>);
+
+impl Output {
+    fn send(&self, message: Value) {
+        let body = message.to_string();
+        let mut stdout = self.0.lock().unwrap();
+        let _ = write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len());
+        let _ = stdout.flush();
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+}
+
+// Serve requests about the exercises on stdin and stdout until the client
+// sends `exit` or closes stdin. Nothing else may be printed to stdout
+// meanwhile, so exercises are compiled and run without the printing functions
+// of `verify` and `run`.
+pub fn serve(exercises: Vec<Exercise>) -> io::Result<()> {
+    let exercises = Arc::new(exercises);
+    let output = Output(Arc::new(Mutex::new(io::stdout())));
+    let mut input = io::stdin().lock();
+    let mut subscribed = false;
+
+    while let Some(body) = read_message(&mut input)? {
+        let request: Request = match serde_json::from_slice::<Value>(&body) {
+            Err(e) => {
+                output.send(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ));
+                continue;
+            }
+            Ok(value) => match serde_json::from_value(value) {
+                Ok(request) => request,
+                Err(e) => {
+                    output.send(error_response(
+                        Value::Null,
+                        RpcError::new(INVALID_REQUEST, e.to_string()),
+                    ));
+                    continue;
+                }
+            },
+        };
+
+        let result = match request.method.as_str() {
+            "exit" => break,
+            "progress/subscribe" if !subscribed => {
+                subscribed = true;
+                subscribe(Arc::clone(&exercises), output.clone()).map(|_| Value::Null)
+            }
+            "progress/subscribe" => Ok(Value::Null),
+            method => handle(&exercises, method, &request.params),
+        };
+        if let Some(id) = request.id {
+            output.send(match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(error) => error_response(id, error),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+// Read the body of the next message, or None once stdin is closed
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length.unwrap_or(0)];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn handle(exercises: &[Exercise], method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "name": "rustlings",
+            "version": crate::VERSION,
+            "methods": METHODS,
+        })),
+        "exercises/list" => Ok(exercises
+            .iter()
+            .map(|e| {
+                json!({
+                    "name": e.name,
+                    "path": e.path,
+                    "mode": e.mode.name(),
+                    "done": e.looks_done(),
+                })
+            })
+            .collect()),
+        "exercise/state" => {
+            let exercise = find(exercises, params)?;
+            Ok(match exercise.state() {
+                State::Done => json!({ "name": exercise.name, "done": true, "context": [] }),
+                State::Pending(context) => json!({
+                    "name": exercise.name,
+                    "done": false,
+                    "context": context
+                        .iter()
+                        .map(|line| json!({
+                            "line": line.line,
+                            "number": line.number,
+                            "important": line.important,
+                        }))
+                        .collect::<Vec<_>>(),
+                }),
+            })
+        }
+        "exercise/run" => Ok(check(find(exercises, params)?)),
+        "exercise/hint" => {
+            let exercise = find(exercises, params)?;
+            Ok(json!({
+                "hint": exercise.hint.text(),
+                "steps": exercise.hints(),
+                "readme": exercise.readme(),
+            }))
+        }
+        "exercise/reset" => {
+            let exercise = find(exercises, params)?;
+            reset(exercise)
+                .map(|()| Value::Null)
+                .map_err(|()| RpcError::new(INTERNAL_ERROR, format!("failed to reset {exercise}")))
+        }
+        "shutdown" => Ok(Value::Null),
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method: {method}"),
+        )),
+    }
+}
+
+// The exercise named by the `name` parameter
+fn find<'a>(exercises: &'a [Exercise], params: &Value) -> Result<&'a Exercise, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing parameter: name"))?;
+    exercises
+        .iter()
+        .find(|e| e.name == name)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("no exercise found for '{name}'")))
+}
+
+// Compile and run the exercise, returning whether it passed and its output
+fn check(exercise: &Exercise) -> Value {
+    let (success, output) = match exercise.compile() {
+        Err(output) => (false, output),
+        Ok(compiled) => match compiled.run() {
+            Ok(output) => (true, output),
+            Err(output) => (false, output),
+        },
+    };
+    history::record(exercise, success);
+    json!({
+        "name": exercise.name,
+        "success": success,
+        "done": exercise.looks_done(),
+        "stdout": output.stdout,
+        "stderr": console::strip_ansi_codes(&output.stderr),
+        "diagnostics": output.diagnostics,
+        "reports": output.reports,
+    })
+}
+
+// Watch the exercises, and notify the client with the result of every
+// exercise that changed, followed by the overall progress
+fn subscribe(exercises: Arc<Vec<Exercise>>, output: Output) -> Result<(), RpcError> {
+    let (tx, rx) = channel::<ChangedFiles>();
+    let watcher = watch_exercises(false, tx).map_err(|e| {
+        RpcError::new(
+            INVALID_REQUEST,
+            format!("failed to watch the exercises: {e}"),
+        )
+    })?;
+    thread::spawn(move || {
+        // The files are watched for as long as the watcher lives
+        let _watcher = watcher;
+        for changed in rx {
+            let Ok(paths) = changed else { continue };
+            let mut indices: Vec<usize> = Vec::new();
+            for path in &paths {
+                for index in affected_exercises(&exercises, path) {
+                    if !indices.contains(&index) {
+                        indices.push(index);
+                    }
+                }
+            }
+            if indices.is_empty() {
+                continue;
+            }
+            for index in indices {
+                output.notify("rustlings/exerciseChanged", check(&exercises[index]));
+            }
+            let done = exercises.iter().filter(|e| e.looks_done()).count();
+            output.notify(
+                "rustlings/progress",
+                json!({ "done": done, "total": exercises.len() }),
+            );
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,316 @@
+use crate::exercise::{Exercise, State};
+use crate::run::{reset, run};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::env;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const COMMANDS: &[&str] = &[
+    "hint", "list", "progress", "run", "reset", "explain", "open", "clear", "goto", "next", "skip",
+    "quit", "help",
+];
+
+// Commands typed in the watch shell that change which exercise watch mode focuses on
+pub enum FocusCommand {
+    Goto(String),
+    Next,
+    Skip,
+}
+
+// What the watch shell knows about watch mode, kept up to date by the watch loop
+pub struct WatchState {
+    pub exercises: Vec<Exercise>,
+    // Index of the exercise watch mode is focused on, which is usually
+    // the one that fails
+    pub focused: usize,
+}
+
+impl WatchState {
+    fn focused(&self) -> &Exercise {
+        &self.exercises[self.focused]
+    }
+}
+
+// Completes command names, and exercise names after `run` and `goto`
+struct ShellHelper {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(' ').map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let candidates = if start == 0 {
+            COMMANDS
+                .iter()
+                .filter(|c| c.starts_with(word))
+                .map(|c| c.to_string())
+                .collect()
+        } else if matches!(line.split_whitespace().next(), Some("run" | "goto")) {
+            self.state
+                .lock()
+                .unwrap()
+                .exercises
+                .iter()
+                .filter(|e| e.name.starts_with(word))
+                .map(|e| e.name.clone())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+pub fn spawn_watch_shell(
+    state: &Arc<Mutex<WatchState>>,
+    should_quit: Arc<AtomicBool>,
+    focus_commands: Sender<FocusCommand>,
+    verbose: bool,
+) {
+    let state = Arc::clone(state);
+    println!("{}", tr!("shell.welcome"));
+    thread::spawn(move || {
+        let mut editor: Editor<ShellHelper, DefaultHistory> = match Editor::new() {
+            Ok(editor) => editor,
+            Err(error) => {
+                println!("{}", tr!("shell.start_failed", error = error));
+                return;
+            }
+        };
+        editor.set_helper(Some(ShellHelper {
+            state: Arc::clone(&state),
+        }));
+        loop {
+            let input = match editor.readline("") {
+                Ok(input) => input,
+                Err(ReadlineError::Interrupted) => {
+                    should_quit.store(true, Ordering::SeqCst);
+                    println!("{}", tr!("shell.bye"));
+                    break;
+                }
+                // Nothing more can be typed, but watch mode keeps running
+                Err(ReadlineError::Eof) => break,
+                Err(error) => {
+                    println!("{}", tr!("shell.read_failed", error = error));
+                    continue;
+                }
+            };
+            let input = input.trim();
+            if input.is_empty() {
+                continue;
+            }
+            let _ = editor.add_history_entry(input);
+
+            let (command, argument) = match input.split_once(' ') {
+                Some((command, argument)) => (command, Some(argument.trim())),
+                None => (input, None),
+            };
+            match (command, argument) {
+                ("hint", None) => println!("{}", state.lock().unwrap().focused().hint),
+                ("list", None) => list(&state.lock().unwrap()),
+                ("progress", None) => progress(&state.lock().unwrap().exercises),
+                ("run", name) => {
+                    let exercise = {
+                        let state = state.lock().unwrap();
+                        match name {
+                            Some(name) => state.exercises.iter().find(|e| e.name == name).cloned(),
+                            None => Some(state.focused().clone()),
+                        }
+                    };
+                    match exercise {
+                        Some(exercise) => {
+                            let _ = run(&exercise, verbose);
+                        }
+                        None => println!(
+                            "{}",
+                            tr!("watch.not_found", name = name.unwrap_or_default())
+                        ),
+                    }
+                }
+                ("reset", None) => {
+                    let exercise = state.lock().unwrap().focused().clone();
+                    if reset(&exercise).is_err() {
+                        println!("{}", tr!("run.reset_failed", exercise = exercise));
+                    }
+                }
+                ("explain", None) => {
+                    let exercise = state.lock().unwrap().focused().clone();
+                    explain(&exercise);
+                }
+                ("open", None) => {
+                    let exercise = state.lock().unwrap().focused().clone();
+                    open(&exercise);
+                }
+                ("clear", None) => println!("\x1B[2J\x1B[1;1H"),
+                ("goto", Some(name)) => {
+                    let _ = focus_commands.send(FocusCommand::Goto(name.to_string()));
+                }
+                ("next", None) => {
+                    let _ = focus_commands.send(FocusCommand::Next);
+                }
+                ("skip", None) => {
+                    let _ = focus_commands.send(FocusCommand::Skip);
+                }
+                ("quit", None) => {
+                    should_quit.store(true, Ordering::SeqCst);
+                    println!("{}", tr!("shell.bye"));
+                    break;
+                }
+                ("help", None) => help(),
+                _ => match input.strip_prefix('!') {
+                    Some(cmd) => {
+                        let parts: Vec<&str> = cmd.split_whitespace().collect();
+                        if parts.is_empty() {
+                            println!("{}", tr!("shell.no_command"));
+                        } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                            println!("{}", tr!("shell.command_failed", command = cmd, error = e));
+                        }
+                    }
+                    None => println!("{}", tr!("shell.unknown", command = input)),
+                },
+            }
+        }
+    });
+}
+
+fn help() {
+    println!("{}", tr!("shell.help"));
+}
+
+fn list(state: &WatchState) {
+    println!(
+        "  {:<17}\t{:<46}\t{:<7}",
+        tr!("list.name"),
+        tr!("list.path"),
+        tr!("list.status")
+    );
+    for (i, e) in state.exercises.iter().enumerate() {
+        let marker = if i == state.focused { '>' } else { ' ' };
+        let status = if e.looks_done() {
+            tr!("list.done")
+        } else {
+            tr!("list.pending")
+        };
+        println!(
+            "{marker} {:<17}\t{:<46}\t{status:<7}",
+            e.name,
+            e.path.display()
+        );
+    }
+}
+
+fn progress(exercises: &[Exercise]) {
+    let exercises_done = exercises.iter().filter(|e| e.looks_done()).count();
+    let percent = format!(
+        "{:.1}",
+        exercises_done as f32 / exercises.len() as f32 * 100.0
+    );
+    println!(
+        "{}",
+        tr!(
+            "shell.progress",
+            done = exercises_done,
+            total = exercises.len(),
+            percent = percent
+        )
+    );
+}
+
+// Compile the exercise again and show rustc's explanation of the first
+// error code in its output
+fn explain(exercise: &Exercise) {
+    let output = match exercise.compile() {
+        Ok(_) => {
+            println!("{}", tr!("shell.nothing_to_explain", exercise = exercise));
+            return;
+        }
+        Err(output) => output,
+    };
+    // Lints have codes like `clippy::float_cmp`, which rustc can't explain
+    let code = output
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .filter_map(|d| d.code.as_deref())
+        .find(|code| code.starts_with('E'));
+    match code {
+        Some(code) => {
+            if let Err(e) = Command::new("rustc").args(["--explain", code]).status() {
+                let command = format!("rustc --explain {code}");
+                println!(
+                    "{}",
+                    tr!("shell.command_failed", command = command, error = e)
+                );
+            }
+        }
+        None => println!("{}", tr!("shell.no_error_code", exercise = exercise)),
+    }
+}
+
+// Open the exercise in the user's editor, at the `I AM NOT DONE` line
+fn open(exercise: &Exercise) {
+    let Some(editor) = env::var("VISUAL").or_else(|_| env::var("EDITOR")).ok() else {
+        println!("{}", tr!("shell.no_editor"));
+        return;
+    };
+    let line = match exercise.state() {
+        State::Done => 1,
+        State::Pending(context) => context
+            .iter()
+            .find(|line| line.important)
+            .map_or(1, |line| line.number),
+    };
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        println!("{}", tr!("shell.empty_editor"));
+        return;
+    };
+    let mut command = Command::new(program);
+    command.args(parts);
+    // VS Code and its forks take the line with the path, most other editors
+    // take it as a `+<line>` argument before the path
+    if matches!(
+        program.rsplit('/').next(),
+        Some("code" | "codium" | "cursor")
+    ) {
+        command
+            .arg("--goto")
+            .arg(format!("{}:{line}", exercise.path.display()));
+    } else {
+        command.arg(format!("+{line}")).arg(&exercise.path);
+    }
+    if let Err(e) = command.status() {
+        println!(
+            "{}",
+            tr!("shell.command_failed", command = editor, error = e)
+        );
+    }
+}
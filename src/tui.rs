@@ -0,0 +1,376 @@
+use crate::exercise::{affected_exercises, Exercise, Mode, State};
+use crate::history;
+use crate::watcher::{watch_exercises, ChangedFiles};
+use crate::WatchStatus;
+use crate::{run, verify};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::Duration;
+
+// The status of an exercise, as far as the interface knows it
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    // Not checked yet in this session and still marked as not done
+    Pending,
+    // Failed to compile, or its tests/binary failed
+    Failed,
+    // Compiles and passes, but still has the `I AM NOT DONE` marker
+    Passing,
+    // Compiles, passes and has no marker left
+    Done,
+}
+
+impl Status {
+    fn symbol(self) -> Span<'static> {
+        match self {
+            Status::Pending => Span::raw("·"),
+            Status::Failed => Span::styled("✗", Style::default().fg(Color::Red)),
+            Status::Passing => Span::styled("~", Style::default().fg(Color::Yellow)),
+            Status::Done => Span::styled("✓", Style::default().fg(Color::Green)),
+        }
+    }
+}
+
+struct App<'a> {
+    exercises: &'a [Exercise],
+    statuses: Vec<Status>,
+    // Index of the exercise shown in the diagnostics and hint panes
+    current: usize,
+    // Plain text output of the last check of the current exercise
+    output: String,
+    // Number of hint paragraphs revealed for the current exercise
+    hints_shown: usize,
+    scroll: u16,
+    message: String,
+    // Whether `r` was pressed once, and the next `r` resets the exercise
+    confirm_reset: bool,
+    success_hints: bool,
+    list_state: ListState,
+}
+
+// Watch the exercises in a full-screen terminal interface instead of
+// the line based watch shell
+pub fn watch(
+    exercises: &[Exercise],
+    success_hints: bool,
+    poll: bool,
+) -> notify::Result<WatchStatus> {
+    let (tx, rx) = channel();
+    let _watcher = watch_exercises(poll, tx)?;
+
+    let mut terminal = ratatui::init();
+    let result = App::new(exercises, success_hints).run(&mut terminal, &rx);
+    ratatui::restore();
+
+    result.map_err(notify::Error::io)
+}
+
+impl<'a> App<'a> {
+    fn new(exercises: &'a [Exercise], success_hints: bool) -> Self {
+        let statuses = exercises
+            .iter()
+            .map(|e| {
+                if e.looks_done() {
+                    Status::Done
+                } else {
+                    Status::Pending
+                }
+            })
+            .collect();
+        App {
+            exercises,
+            statuses,
+            current: 0,
+            output: String::new(),
+            hints_shown: 0,
+            scroll: 0,
+            message: String::new(),
+            confirm_reset: false,
+            success_hints,
+            list_state: ListState::default(),
+        }
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        rx: &Receiver<ChangedFiles>,
+    ) -> io::Result<WatchStatus> {
+        // Like the watch shell, start by checking every exercise in order
+        let all: Vec<usize> = (0..self.exercises.len()).collect();
+        if self.verify(terminal, all)? {
+            return Ok(WatchStatus::Finished);
+        }
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(200))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    // Any other key than a second `r` cancels the reset
+                    let confirming = std::mem::take(&mut self.confirm_reset);
+                    if confirming && key.code != KeyCode::Char('r') {
+                        self.message.clear();
+                    }
+                    let finished = match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(WatchStatus::Unfinished),
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            return Ok(WatchStatus::Unfinished)
+                        }
+                        KeyCode::Char('h') => {
+                            self.next_hint();
+                            false
+                        }
+                        KeyCode::Char('t') => self.verify_from(terminal, self.current)?,
+                        KeyCode::Char('s') => self.skip(terminal)?,
+                        KeyCode::Char('r') if confirming => self.reset(terminal)?,
+                        KeyCode::Char('r') => {
+                            self.confirm_reset = true;
+                            self.message =
+                                tr!("tui.confirm_reset", exercise = self.exercises[self.current]);
+                            false
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            self.scroll = self.scroll.saturating_sub(1);
+                            false
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            self.scroll = self.scroll.saturating_add(1);
+                            false
+                        }
+                        KeyCode::PageUp => {
+                            self.scroll = self.scroll.saturating_sub(10);
+                            false
+                        }
+                        KeyCode::PageDown => {
+                            self.scroll = self.scroll.saturating_add(10);
+                            false
+                        }
+                        _ => false,
+                    };
+                    if finished {
+                        return Ok(WatchStatus::Finished);
+                    }
+                }
+            }
+
+            match rx.try_recv() {
+                Ok(Ok(paths)) => {
+                    let changed = paths
+                        .iter()
+                        .find_map(|path| affected_exercises(self.exercises, path).first().copied());
+                    if let Some(index) = changed {
+                        if self.verify_from(terminal, index)? {
+                            return Ok(WatchStatus::Finished);
+                        }
+                    }
+                }
+                Ok(Err(errors)) => {
+                    self.message = tr!("watch.error", error = format!("{errors:?}"));
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    self.message = tr!("tui.watcher_stopped");
+                }
+            }
+        }
+    }
+
+    // Check the given exercise first, then every exercise that is not done
+    // yet in the recommended order.
+    // Returns true when there is nothing left to do.
+    fn verify_from(&mut self, terminal: &mut DefaultTerminal, first: usize) -> io::Result<bool> {
+        let order = std::iter::once(first)
+            .chain(
+                (0..self.exercises.len())
+                    .filter(|&i| i != first && self.statuses[i] != Status::Done),
+            )
+            .collect();
+        self.verify(terminal, order)
+    }
+
+    // Check the given exercises, stopping at the first one that needs work.
+    // Returns true if all of them are done.
+    fn verify(&mut self, terminal: &mut DefaultTerminal, order: Vec<usize>) -> io::Result<bool> {
+        for index in order {
+            if !self.check(terminal, index)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Move on to the next exercise that is not done, without waiting for
+    // the current one to pass
+    fn skip(&mut self, terminal: &mut DefaultTerminal) -> io::Result<bool> {
+        let len = self.exercises.len();
+        let next = (1..len)
+            .map(|offset| (self.current + offset) % len)
+            .find(|&i| self.statuses[i] != Status::Done);
+        match next {
+            Some(index) => {
+                self.check(terminal, index)?;
+                Ok(false)
+            }
+            None => {
+                self.message = tr!("watch.nothing_to_skip");
+                Ok(false)
+            }
+        }
+    }
+
+    // Reset the current exercise with `git stash` and check it again
+    fn reset(&mut self, terminal: &mut DefaultTerminal) -> io::Result<bool> {
+        let exercise = &self.exercises[self.current];
+        let message = match run::reset(exercise) {
+            Ok(()) => tr!("run.reset", exercise = exercise),
+            Err(()) => tr!("run.reset_failed", exercise = exercise),
+        };
+        let finished = self.verify_from(terminal, self.current)?;
+        self.message = message;
+        Ok(finished)
+    }
+
+    // Compile and run a single exercise, and make it the current one if it
+    // still needs work. Returns true if the exercise is done.
+    fn check(&mut self, terminal: &mut DefaultTerminal, index: usize) -> io::Result<bool> {
+        let exercise = &self.exercises[index];
+        self.message = tr!("tui.checking", exercise = exercise);
+        terminal.draw(|frame| self.draw(frame))?;
+
+        let (status, output) = check(exercise);
+        self.statuses[index] = status;
+        self.message.clear();
+        if status == Status::Done {
+            return Ok(true);
+        }
+        if index != self.current {
+            self.hints_shown = 0;
+        }
+        if status == Status::Passing && self.success_hints {
+            self.hints_shown = exercise.hints().len();
+        }
+        self.current = index;
+        self.output = output;
+        self.scroll = 0;
+        Ok(false)
+    }
+
+    fn next_hint(&mut self) {
+        let hints = self.exercises[self.current].hints().len();
+        if self.hints_shown < hints {
+            self.hints_shown += 1;
+        } else {
+            self.message = tr!("tui.no_more_hints");
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+        let [list_area, right] =
+            Layout::horizontal([Constraint::Length(28), Constraint::Min(0)]).areas(main);
+        let [output_area, hint_area] =
+            Layout::vertical([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(right);
+
+        let items: Vec<ListItem> = self
+            .exercises
+            .iter()
+            .zip(&self.statuses)
+            .map(|(exercise, status)| {
+                ListItem::new(Line::from(vec![
+                    status.symbol(),
+                    Span::raw(" "),
+                    Span::raw(exercise.name.as_str()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" {} ", tr!("tui.exercises"))))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        self.list_state.select(Some(self.current));
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let exercise = &self.exercises[self.current];
+        let output = Paragraph::new(self.output.as_str())
+            .block(Block::bordered().title(format!(" {exercise} ")))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(output, output_area);
+
+        let hints = exercise.hints();
+        let hint_text = if self.hints_shown == 0 {
+            Text::from(tr!("tui.hints_available", count = hints.len()).dim())
+        } else {
+            Text::from(hints[..self.hints_shown].join("\n\n"))
+        };
+        let hint = Paragraph::new(hint_text)
+            .block(Block::bordered().title(format!(
+                " {} ",
+                tr!("tui.hint", shown = self.hints_shown, total = hints.len())
+            )))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(hint, hint_area);
+
+        let done = self.statuses.iter().filter(|&&s| s == Status::Done).count();
+        let total = self.exercises.len();
+        let percent = format!("{:.1}", done as f32 / total as f32 * 100.0);
+        let progress = format!(
+            "{}  {}",
+            tr!(
+                "tui.progress",
+                done = done,
+                total = total,
+                percent = percent
+            ),
+            self.message
+        );
+        let footer_text = Text::from(vec![
+            Line::from(progress),
+            Line::from(tr!("tui.keys").dim()),
+        ]);
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+}
+
+// Compile and run the exercise without printing anything, returning its
+// status and the text to show in the diagnostics pane, as `verify` prints it
+fn check(exercise: &Exercise) -> (Status, String) {
+    let (status, text) = match exercise.compile() {
+        Err(output) => {
+            history::record(exercise, false);
+            let (message, details) = verify::compile_failure(exercise, &output);
+            (Status::Failed, format!("{message}\n\n{details}"))
+        }
+        Ok(compiled) => match compiled.run() {
+            Err(output) => {
+                history::record(exercise, false);
+                let (message, details) = verify::run_failure(exercise, &output);
+                (Status::Failed, format!("{message}\n\n{details}"))
+            }
+            Ok(output) => {
+                history::record(exercise, true);
+                let mut text: String = output.reports.iter().map(|r| format!("{r}\n")).collect();
+                match exercise.state() {
+                    State::Done => (Status::Done, text),
+                    State::Pending(context) => {
+                        // The hints have their own pane
+                        let stdout =
+                            (exercise.mode == Mode::Compile).then_some(output.stdout.as_str());
+                        text += &verify::pending(exercise, context, stdout, false);
+                        (Status::Passing, text)
+                    }
+                }
+            }
+        },
+    };
+    (status, console::strip_ansi_codes(&text).into_owned())
+}
@@ -1,4 +1,6 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::exercise::{CompiledExercise, ContextLine, Exercise, ExerciseOutput, Mode, State};
+use crate::explanations::explanations;
+use crate::history;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
@@ -17,9 +19,10 @@ pub fn verify<'a>(
     let (num_done, total) = progress;
     let bar = ProgressBar::new(total as u64);
     let mut percentage = num_done as f32 / total as f32 * 100.0;
-    bar.set_style(ProgressStyle::default_bar()
-        .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-        .progress_chars("#>-")
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
+            .progress_chars("#>-"),
     );
     bar.set_position(num_done as u64);
     bar.set_message(format!("({:.1} %)", percentage));
@@ -29,8 +32,9 @@ pub fn verify<'a>(
             Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
             Mode::Compile => compile_and_run_interactively(exercise, success_hints),
             Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
+            Mode::BuildScript => {
+                compile_and_test(exercise, RunMode::Interactive, verbose, success_hints)
+            }
         };
         if !compile_result.unwrap_or(false) {
             return Err(exercise);
@@ -48,53 +52,71 @@ enum RunMode {
 }
 
 // Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ExerciseOutput> {
     compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
     Ok(())
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ExerciseOutput> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
+    progress_bar.set_message(tr!("verify.compiling", exercise = exercise));
     progress_bar.enable_steady_tick(100);
 
     let _ = compile(exercise, &progress_bar)?;
     progress_bar.finish_and_clear();
+    history::record(exercise, true);
 
     Ok(prompt_for_completion(exercise, None, success_hints))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_run_interactively(
+    exercise: &Exercise,
+    success_hints: bool,
+) -> Result<bool, ExerciseOutput> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
+    progress_bar.set_message(tr!("verify.compiling", exercise = exercise));
     progress_bar.enable_steady_tick(100);
 
     let compilation = compile(exercise, &progress_bar)?;
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(tr!("verify.running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     let output = match result {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-            return Err(());
+            history::record(exercise, false);
+            let (message, details) = run_failure(exercise, &output);
+            warn!("{}", message);
+            print!("{details}");
+            return Err(output);
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    history::record(exercise, true);
+    for report in &output.reports {
+        println!("{report}");
+    }
+    Ok(prompt_for_completion(
+        exercise,
+        Some(output.stdout),
+        success_hints,
+    ))
 }
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_test(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    verbose: bool,
+    success_hints: bool,
+) -> Result<bool, ExerciseOutput> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
+    progress_bar.set_message(tr!("verify.testing", exercise = exercise));
     progress_bar.enable_steady_tick(100);
 
     let compilation = compile(exercise, &progress_bar)?;
@@ -103,9 +125,13 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
 
     match result {
         Ok(output) => {
+            history::record(exercise, true);
             if verbose {
                 println!("{}", output.stdout);
             }
+            for report in &output.reports {
+                println!("{report}");
+            }
             if let RunMode::Interactive = run_mode {
                 Ok(prompt_for_completion(exercise, None, success_hints))
             } else {
@@ -113,93 +139,140 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
             }
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stdout);
-            Err(())
+            history::record(exercise, false);
+            let (message, details) = run_failure(exercise, &output);
+            warn!("{}", message);
+            print!("{details}");
+            Err(output)
         }
     }
 }
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
+    progress_bar: &ProgressBar,
+) -> Result<CompiledExercise<'a>, ExerciseOutput> {
     let compilation_result = exercise.compile();
 
     match compilation_result {
         Ok(compilation) => Ok(compilation),
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stderr);
-            Err(())
+            history::record(exercise, false);
+            let (message, details) = compile_failure(exercise, &output);
+            warn!("{}", message);
+            print!("{details}");
+            Err(output)
         }
     }
 }
 
-fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
+// The message shown when an exercise fails to compile, and the compiler
+// output to show below it
+pub fn compile_failure(exercise: &Exercise, output: &ExerciseOutput) -> (String, String) {
+    let message = tr!("verify.compiling_failed", exercise = exercise);
+    let details = format!(
+        "{}\n{}",
+        output.stderr,
+        explanations(exercise, &output.diagnostics)
+    );
+    (message, details)
+}
+
+// The message shown when the binary or the tests of an exercise fail, and
+// their output to show below it
+pub fn run_failure(exercise: &Exercise, output: &ExerciseOutput) -> (String, String) {
+    let message = match exercise.mode {
+        Mode::Test | Mode::BuildScript => tr!("verify.testing_failed", exercise = exercise),
+        Mode::Compile | Mode::Clippy => tr!("verify.ran_with_errors", exercise = exercise),
+    };
+    let mut details = format!("{}\n", output.stdout);
+    // Like the report of Miri, for the exercises checked with it
+    if !output.stderr.is_empty() {
+        details += &format!("{}\n", output.stderr);
+    }
+    (message, details)
+}
+
+fn prompt_for_completion(
+    exercise: &Exercise,
+    prompt_output: Option<String>,
+    success_hints: bool,
+) -> bool {
     let context = match exercise.state() {
         State::Done => return true,
         State::Pending(context) => context,
     };
     match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
-        Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+        Mode::Compile => success!("{}", tr!("verify.ran", exercise = exercise)),
+        Mode::Test => success!("{}", tr!("verify.tested", exercise = exercise)),
+        Mode::Clippy => success!("{}", tr!("verify.compiled", exercise = exercise)),
+        Mode::BuildScript => success!("{}", tr!("verify.compiled", exercise = exercise)),
     }
+    print!(
+        "{}",
+        pending(exercise, context, prompt_output.as_deref(), success_hints)
+    );
+    false
+}
 
+// What is shown for an exercise that passes but still has its `I AM NOT
+// DONE` marker: the output of its binary and its hints, if asked for, and
+// the lines around the marker
+pub fn pending(
+    exercise: &Exercise,
+    context: Vec<ContextLine>,
+    prompt_output: Option<&str>,
+    success_hints: bool,
+) -> String {
     let no_emoji = env::var("NO_EMOJI").is_ok();
 
     let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
+        tr!("verify.clippy_happy")
     } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
+        tr!("verify.clippy_happy_emoji")
     };
 
     let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
+        Mode::Compile => tr!("verify.compiles"),
+        Mode::Test => tr!("verify.tests_pass"),
         Mode::Clippy => clippy_success_msg,
-        Mode::BuildScript => "Build script works!",
+        Mode::BuildScript => tr!("verify.build_script_works"),
     };
-    println!();
+    let mut text = String::from("\n");
     if no_emoji {
-        println!("~*~ {success_msg} ~*~")
+        text += &format!("~*~ {success_msg} ~*~\n");
     } else {
-        println!("🎉 🎉  {success_msg} 🎉 🎉")
+        text += &format!("🎉 🎉  {success_msg} 🎉 🎉\n");
     }
-    println!();
+    text += "\n";
 
     if let Some(output) = prompt_output {
-        println!("Output:");
-        println!("{}", separator());
-        println!("{output}");
-        println!("{}", separator());
-        println!();
+        text += &format!("{}\n", tr!("verify.output"));
+        text += &format!("{}\n", separator());
+        text += &format!("{output}\n");
+        text += &format!("{}\n", separator());
+        text += "\n";
     }
     if success_hints {
-        println!("Hints:");
-        println!("{}", separator());
-        println!("{}", exercise.hint);
-        println!("{}", separator());
-        println!();
+        text += &format!("{}\n", tr!("verify.hints"));
+        text += &format!("{}\n", separator());
+        text += &format!("{}\n", exercise.hint);
+        text += &format!("{}\n", separator());
+        text += "\n";
     }
 
-    println!("You can keep working on this exercise,");
-    println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
+    text += &format!("{}\n", tr!("verify.keep_working"));
+    text += &format!(
+        "{}\n",
+        tr!(
+            "verify.next_exercise",
+            marker = style("`I AM NOT DONE`").bold()
+        )
     );
-    println!();
+    text += "\n";
     for context_line in context {
         let formatted_line = if context_line.important {
             format!("{}", style(context_line.line).bold())
@@ -207,15 +280,14 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
             context_line.line.to_string()
         };
 
-        println!(
-            "{:>2} {}  {}",
+        text += &format!(
+            "{:>2} {}  {}\n",
             style(context_line.number).blue().bold(),
             style("|").blue(),
             formatted_line
         );
     }
-
-    false
+    text
 }
 
 fn separator() -> console::StyledObject<&'static str> {
@@ -0,0 +1,113 @@
+use notify::event::{MetadataKind, ModifyKind, RenameMode};
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{
+    new_debouncer, new_debouncer_opt, DebounceEventResult, Debouncer, NoCache, RecommendedCache,
+};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+// How long a burst of events, like an editor saving a file, has to settle
+// before the changed files are reported
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+// How often the watched files are scanned when polling
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// The canonical paths of the files that changed in a burst of events
+pub type ChangedFiles = Result<Vec<PathBuf>, Vec<notify::Error>>;
+
+// Watches files for changes, either with the platform's native file
+// notifications or by polling. Changes stop being reported once it's dropped.
+pub enum FileWatcher {
+    Native(Debouncer<RecommendedWatcher, RecommendedCache>),
+    Polling(Debouncer<PollWatcher, NoCache>),
+}
+
+// Watch the exercises and the directory holding info.toml. The latter is
+// watched as a whole, so that info.toml is still followed after an editor
+// replaces the file when saving it.
+pub fn watch_exercises(poll: bool, tx: Sender<ChangedFiles>) -> notify::Result<FileWatcher> {
+    FileWatcher::new(
+        &[
+            (Path::new("./exercises"), RecursiveMode::Recursive),
+            (Path::new("."), RecursiveMode::NonRecursive),
+        ],
+        poll,
+        tx,
+    )
+}
+
+impl FileWatcher {
+    // Watch the given paths and send the files that changed to `tx`.
+    // Polling is used if `poll` is set, or if the native notifications are
+    // unavailable, which is common on network filesystems and in containers.
+    pub fn new(
+        paths: &[(&Path, RecursiveMode)],
+        poll: bool,
+        tx: Sender<ChangedFiles>,
+    ) -> notify::Result<FileWatcher> {
+        if !poll {
+            let native = new_debouncer(DEBOUNCE_TIMEOUT, None, handler(tx.clone()))
+                .map(FileWatcher::Native)
+                .and_then(|mut watcher| watcher.watch_all(paths).map(|_| watcher));
+            match native {
+                Ok(watcher) => return Ok(watcher),
+                // On stderr, as stdout may carry the JSON-RPC of `serve --stdio`
+                Err(e) => eprintln!(
+                    "Native file notifications are unavailable ({e}), polling for changes instead."
+                ),
+            }
+        }
+        let mut watcher = FileWatcher::Polling(new_debouncer_opt(
+            DEBOUNCE_TIMEOUT,
+            None,
+            handler(tx),
+            NoCache,
+            Config::default().with_poll_interval(POLL_INTERVAL),
+        )?);
+        watcher.watch_all(paths)?;
+        Ok(watcher)
+    }
+
+    fn watch_all(&mut self, paths: &[(&Path, RecursiveMode)]) -> notify::Result<()> {
+        for &(path, mode) in paths {
+            match self {
+                FileWatcher::Native(debouncer) => debouncer.watch(path, mode)?,
+                FileWatcher::Polling(debouncer) => debouncer.watch(path, mode)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Turn debounced events into the list of files that now hold new contents.
+// Editors that save by writing a temporary file and renaming it over the
+// original are covered by taking the destination of renames.
+fn handler(tx: Sender<ChangedFiles>) -> impl FnMut(DebounceEventResult) + Send + 'static {
+    move |result: DebounceEventResult| {
+        let changed = result.map(|events| {
+            let mut paths: Vec<PathBuf> = Vec::new();
+            for event in events {
+                let path = match event.kind {
+                    EventKind::Create(_)
+                    | EventKind::Modify(
+                        ModifyKind::Any
+                        | ModifyKind::Data(_)
+                        | ModifyKind::Metadata(
+                            MetadataKind::Any | MetadataKind::WriteTime | MetadataKind::Permissions,
+                        )
+                        | ModifyKind::Name(RenameMode::To | RenameMode::Both | RenameMode::Any),
+                    ) => event.paths.last(),
+                    _ => None,
+                };
+                if let Some(path) = path.and_then(|p| p.canonicalize().ok()) {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+            }
+            paths
+        });
+        let _ = tx.send(changed);
+    }
+}
@@ -0,0 +1,7 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn says_hello() {
+        assert_eq!(greeting::hello("test"), "Hello, test!");
+    }
+}
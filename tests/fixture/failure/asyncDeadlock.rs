@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod tests {
+    rustlings_runtime::async_test! {
+        async fn never_completes() {
+            std::future::pending::<()>().await
+        }
+    }
+}
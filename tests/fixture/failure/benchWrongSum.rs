@@ -0,0 +1,18 @@
+fn sum(n: u64) -> u64 {
+    (0..n).sum()
+}
+
+#[cfg(rustlings_bench)]
+pub fn bench(input: &[u64]) -> u64 {
+    sum(input.len() as u64) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(4), 6);
+    }
+}
@@ -0,0 +1,17 @@
+fn sign(number: i32) -> &'static str {
+    if number < 0 {
+        "negative"
+    } else {
+        "positive"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs() {
+        assert_eq!(sign(1), "positive");
+    }
+}
@@ -0,0 +1,14 @@
+fn sum(numbers: &[u32]) -> u32 {
+    let boxed: &u32 = Box::leak(Box::new(numbers.iter().sum()));
+    *boxed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(&[1, 2, 3]), 6);
+    }
+}
@@ -0,0 +1,17 @@
+fn reverse(numbers: &[u32]) -> Vec<u32> {
+    numbers.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverses() {
+        rustlings_check::check(|numbers: Vec<u32>| {
+            let mut expected = numbers.clone();
+            expected.reverse();
+            reverse(&numbers) == expected
+        });
+    }
+}
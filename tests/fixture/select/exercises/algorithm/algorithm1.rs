@@ -0,0 +1,3 @@
+// algorithm1
+
+fn main() {}
@@ -0,0 +1,3 @@
+// algorithm2
+
+fn main() {}
@@ -0,0 +1,5 @@
+// intro1
+
+fn main() {
+    println!("Hello!");
+}
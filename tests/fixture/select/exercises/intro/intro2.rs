@@ -0,0 +1,7 @@
+// intro2
+
+// I AM NOT DONE
+
+fn main() {
+    println!("{}", x);
+}
@@ -0,0 +1,15 @@
+use rustlings_runtime::{join, sleep, spawn};
+use std::time::Duration;
+
+async fn double(n: u32) -> u32 {
+    sleep(Duration::from_millis(10)).await;
+    n * 2
+}
+
+rustlings_runtime::async_main! {
+    async fn main() {
+        let handle = spawn(double(1));
+        let (a, b) = join(double(2), double(3)).await;
+        println!("{} {} {}", handle.await, a, b);
+    }
+}
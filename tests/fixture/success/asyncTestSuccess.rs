@@ -0,0 +1,38 @@
+use rustlings_runtime::{sleep, spawn};
+use std::time::Duration;
+
+async fn slow_double(n: u64) -> u64 {
+    sleep(Duration::from_millis(200)).await;
+    n * 2
+}
+
+async fn double_all(numbers: Vec<u64>) -> Vec<u64> {
+    let mut handles = Vec::new();
+    for n in numbers {
+        handles.push(spawn(slow_double(n)));
+    }
+
+    let mut results = Vec::new();
+    for handle in handles {
+        results.push(handle.await);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    rustlings_runtime::async_test! {
+        async fn doubles_all_numbers() {
+            assert_eq!(double_all(vec![1, 2, 3]).await, vec![2, 4, 6]);
+        }
+
+        async fn doubles_at_the_same_time() {
+            let start = Instant::now();
+            assert_eq!(double_all(vec![5; 10]).await, vec![10; 10]);
+            assert!(start.elapsed() < Duration::from_secs(1));
+        }
+    }
+}
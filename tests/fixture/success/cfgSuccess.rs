@@ -0,0 +1,5 @@
+#[cfg(not(rustlings))]
+compile_error!("the cfg of the exercise isn't set");
+
+fn main() {
+}
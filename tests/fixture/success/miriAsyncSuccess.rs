@@ -0,0 +1,18 @@
+use rustlings_runtime::{join, spawn};
+
+async fn double(n: u32) -> u32 {
+    n * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    rustlings_runtime::async_test! {
+        async fn doubles_in_tasks() {
+            let handle = spawn(double(1));
+            let (a, b) = join(double(2), double(3)).await;
+            assert_eq!((handle.await, a, b), (2, 4, 6));
+        }
+    }
+}
@@ -0,0 +1,13 @@
+fn reverse(numbers: &[u32]) -> Vec<u32> {
+    numbers.iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversing_twice_gives_the_same() {
+        rustlings_check::check(|numbers: Vec<u32>| reverse(&reverse(&numbers)) == numbers);
+    }
+}
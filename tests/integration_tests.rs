@@ -13,9 +13,11 @@ fn runs_without_arguments() {
 
 #[test]
 fn fails_when_in_wrong_dir() {
+    // Subdirectories of the checkout are fine, so this needs to be outside
     Command::cargo_bin("rustlings")
         .unwrap()
-        .current_dir("tests/")
+        .current_dir(std::env::temp_dir())
+        .env_remove("RUSTLINGS_ROOT")
         .assert()
         .code(1);
 }
@@ -44,7 +46,17 @@ fn verify_fails_if_some_fails() {
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_compile_with_cfg() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cfgSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -54,7 +66,7 @@ fn run_single_compile_success() {
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -64,7 +76,7 @@ fn run_single_compile_failure() {
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -74,7 +86,7 @@ fn run_single_test_success() {
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -84,7 +96,7 @@ fn run_single_test_failure() {
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -104,7 +116,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -114,7 +126,7 @@ fn run_single_test_no_exercise() {
 fn reset_single_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["reset", "intro1"])
         .assert()
         .code(0);
 }
@@ -135,7 +147,7 @@ fn reset_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -171,7 +183,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -182,7 +194,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -193,7 +205,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -204,7 +216,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -215,7 +227,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -225,7 +237,7 @@ fn run_rustlings_list() {
 fn run_rustlings_list_no_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success()
@@ -236,7 +248,7 @@ fn run_rustlings_list_no_pending() {
 fn run_rustlings_list_both_done_and_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -247,7 +259,7 @@ fn run_rustlings_list_both_done_and_pending() {
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
+        .args(["list", "--solved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -258,9 +270,634 @@ fn run_rustlings_list_without_pending() {
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+// Frame a JSON-RPC message the way the server expects it
+fn rpc_message(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{body}", body.len())
+}
+
+#[test]
+fn serve_answers_requests() {
+    let input = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"exercises/list"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"exercise/hint","params":{"name":"compSuccess"}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"exercise/run","params":{"name":"testSuccess"}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"exercise/state","params":{"name":"missing"}}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ]
+    .map(rpc_message)
+    .concat();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["serve", "--stdio"])
+        .current_dir("tests/fixture/success")
+        .with_stdin()
+        .buffer(input)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(r#""name":"compSuccess""#))
+        .stdout(predicates::str::contains(r#""hint":"""#))
+        .stdout(predicates::str::contains(r#""success":true"#))
+        .stdout(predicates::str::contains(r#""code":-32602"#));
+}
+
+#[test]
+fn lsp_check_reports_missing_project() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--check"])
+        // Any existing directory will do, the sources aren't read
+        .env("RUST_SRC_PATH", ".")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "rust-project.json is out of date",
+        ));
+}
+
+#[test]
+fn lsp_fails_with_missing_rust_src() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--check"])
+        .env("RUST_SRC_PATH", "does/not/exist")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "RUST_SRC_PATH is set to `does/not/exist`",
+        ));
+}
+
+#[test]
+fn doctor_reports_rustc() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("doctor")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("rustc"));
+}
+
+#[test]
+fn run_exercise_with_vendored_crates() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cratesSuccess"])
+        .current_dir("tests/fixture/crates")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello, rustlings!"));
+}
+
+#[test]
+fn test_exercise_with_vendored_crates() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cratesTest"])
+        .current_dir("tests/fixture/crates")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_exercise_with_crates_fails_without_vendor_dir() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cratesNoVendor"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "the `vendor` directory, but it is missing",
+        ));
+}
+
+#[test]
+fn run_exercise_with_crates_fails_without_its_file() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cratesMissing"])
+        .current_dir("tests/fixture/crates")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "Failed to build the cargo project of cratesMissing.rs",
+        ));
+}
+
+#[test]
+fn run_async_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "asyncSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 4 6"));
+}
+
+#[test]
+fn test_async_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "asyncTestSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn async_exercise_times_out() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "asyncDeadlock"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("didn't complete within 1s"));
+}
+
+#[test]
+fn run_miri_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "miriSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_miri_exercise_with_bundled_crates() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "miriAsyncSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn miri_fails_leaking_exercise() {
+    let miri_installed = [&[][..], &["+nightly"][..]].iter().any(|toolchain| {
+        Command::new("cargo")
+            .args(*toolchain)
+            .args(["miri", "--version"])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    });
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "miriLeak"])
+        .current_dir("tests/fixture/failure")
+        .assert();
+    // Without Miri, passing the tests is enough
+    if miri_installed {
+        assert
+            .code(1)
+            .stdout(predicates::str::contains("memory leaked"));
+    } else {
+        assert.success();
+    }
+}
+
+#[test]
+fn run_exercise_with_benchmark() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Benchmark of benchSuccess.rs"));
+}
+
+#[test]
+fn benchmark_fails_slow_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchTooSlow"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("too slow for inputs of size 10"));
+}
+
+#[test]
+fn benchmark_fails_exercise_with_another_checksum() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchWrongSum"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "returned 46 for inputs of size 10, but the reference solution returned 45",
+        ));
+}
+
+#[test]
+fn benchmark_tells_crashes_from_slow_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "benchCrash"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("crashed for inputs of size 1000")
+                .and(predicates::str::contains("too many numbers")),
+        );
+}
+
+#[test]
+fn run_exercise_with_property_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "propSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn failing_property_test_shows_its_seed() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "propFailure"])
+        .env("RUSTLINGS_SEED", "0x2a")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("smallest failing input: [0, 1]")
+                .and(predicates::str::contains("RUSTLINGS_SEED=0x2a")),
+        );
+}
+
+#[test]
+fn run_exercise_with_coverage() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "coverageSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn coverage_shows_lines_the_tests_dont_run() {
+    let llvm_tools_installed = Command::new("rustc")
+        .args(["--print", "target-libdir"])
+        .output()
+        .is_ok_and(|output| {
+            let libdir = String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string();
+            std::path::Path::new(&libdir)
+                .with_file_name("bin")
+                .join(format!("llvm-cov{}", std::env::consts::EXE_SUFFIX))
+                .is_file()
+        });
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "coverageLow"])
+        .current_dir("tests/fixture/failure")
+        .assert();
+    // Without the LLVM tools, passing the tests is enough
+    if llvm_tools_installed {
+        assert.code(1).stdout(predicates::str::contains(
+            "Lines the tests don't run:\n   3 |",
+        ));
+    } else {
+        assert.success();
+    }
+}
+
+#[test]
+fn selftest_passes_with_solutions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["selftest", "--filter", "selftestPass,selftestMarker"])
+        .current_dir("tests/fixture/selftest")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 passed, 0 failed, 0 skipped"));
+}
+
+#[test]
+fn selftest_selects_exercises_like_the_other_subcommands() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["selftest", "--filter", "selftestP*,/Marker$/"])
+        .current_dir("tests/fixture/selftest")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 passed, 0 failed, 0 skipped"));
+}
+
+#[test]
+fn selftest_fails_without_solutions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["selftest", "--filter", "selftestMissing"])
+        .current_dir("tests/fixture/selftest")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercise was tested"));
+}
+
+#[test]
+fn selftest_reports_broken_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "selftest",
+            "--filter",
+            "selftestBroken,selftestSolved,selftestMissing",
+        ])
+        .current_dir("tests/fixture/selftest")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("selftestBroken: the solution doesn't pass")
+                .and(predicates::str::contains(
+                    "selftestSolved: the unsolved exercise already passes",
+                ))
+                .and(predicates::str::contains(
+                    "selftestMissing: there is no solutions/selftestMissing.rs",
+                ))
+                .and(predicates::str::contains("0 passed, 2 failed, 1 skipped")),
+        );
+}
+
+#[test]
+fn run_exercise_of_default_course() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "basics1"])
+        .current_dir("tests/fixture/courses")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello from the basics!"));
+}
+
+#[test]
+fn run_exercise_of_selected_course() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--course", "extra", "run", "extra1"])
+        .current_dir("tests/fixture/courses")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello from the extras!"));
+}
+
+#[test]
+fn unknown_course_fails() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--course", "advanced", "list"])
+        .current_dir("tests/fixture/courses")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("the courses are: basics, extra"));
+}
+
+#[test]
+fn courses_show_their_progress() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("courses")
+        .current_dir("tests/fixture/courses")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("1 / 1 exercises (100.0 %)")
+                .and(predicates::str::contains("1 / 2 exercises (50.0 %)")),
+        );
+}
+
+#[test]
+fn run_from_subdirectory() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "extra1"])
+        .current_dir("tests/fixture/courses/extra/exercises")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello from the extras!"));
+}
+
+#[test]
+fn run_with_root_from_environment() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .env("RUSTLINGS_ROOT", "tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn hint_in_chosen_language() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh", "hint", "greet1"])
+        .current_dir("tests/fixture/i18n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("打个招呼。").and(predicates::str::contains(
+                "exercises/greetings/README.zh.md",
+            )),
+        );
+}
+
+#[test]
+fn hint_falls_back_to_english() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "fr", "hint", "greet1"])
+        .current_dir("tests/fixture/i18n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Say hello.")
+                .and(predicates::str::contains("exercises/greetings/README.md")),
+        );
+}
+
+#[test]
+fn messages_in_language_of_locale() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greet1"])
+        .env("LANG", "zh_CN.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .current_dir("tests/fixture/i18n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("成功运行"));
+}
+
+#[test]
+fn run_rustlings_list_as_json() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--format", "json"])
+        .current_dir("tests/fixture/state")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let exercises: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let finished = exercises
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["name"] == "finished_exercise")
+        .unwrap();
+    assert_eq!(finished["path"], "finished_exercise.rs");
+    assert_eq!(finished["mode"], "compile");
+    assert_eq!(finished["status"], "done");
+    assert_eq!(finished["hints"], 0);
+}
+
+#[test]
+fn run_rustlings_list_as_csv() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--format", "csv", "--sort", "name"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::starts_with(
+                "name,path,mode,topic,status,last_verified,hints\n\
+                 finished_exercise,finished_exercise.rs,compile,,done,",
+            )
+            .and(predicates::str::contains("Progress").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_grouped_by_topic() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--group-by", "topic"])
+        .current_dir("tests/fixture/courses/extra")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("other (1/2)"));
+}
+
+#[test]
+fn hint_exercises_matching_a_glob() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "algorithm*"])
+        .current_dir("tests/fixture/select")
+        .assert()
+        .success()
+        .stdout("algorithm1:\nSort the list.\n\nalgorithm2:\nSearch the list.\n");
+}
+
+#[test]
+fn list_exercises_matching_topics_and_regexes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--filter", "intro,/2$/"])
+        .current_dir("tests/fixture/select")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("intro1\nintro2\nalgorithm2\n"));
+}
+
+#[test]
+fn misspelled_exercise_suggests_names() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "algoritm1"])
+        .current_dir("tests/fixture/select")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Did you mean algorithm1"));
+}
+
+#[test]
+fn hint_failed_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir("tests/fixture/select")
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "failed"])
+        .current_dir("tests/fixture/select")
+        .assert()
+        .success()
+        .stdout("Declare x.\n");
+}
+
+#[test]
+fn reset_goes_on_after_a_failure() {
+    // Outside of a git repository, every exercise fails to reset
+    let dir = std::env::temp_dir().join(format!("rustlings-reset-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut info = String::new();
+    for name in ["reset1", "reset2"] {
+        std::fs::write(dir.join(format!("{name}.rs")), "fn main() {}\n").unwrap();
+        info += &format!(
+            "[[exercises]]\nname = \"{name}\"\npath = \"{name}.rs\"\nmode = \"compile\"\nhint = \"\"\n\n"
+        );
+    }
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "reset*"])
+        .current_dir(&dir)
+        .env_remove("RUSTLINGS_ROOT")
+        .env("GIT_CEILING_DIRECTORIES", std::env::temp_dir())
+        .assert();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert.code(1).stdout(
+        predicates::str::contains("Failed to reset reset1.rs")
+            .and(predicates::str::contains("Failed to reset reset2.rs")),
+    );
+}
+
+#[test]
+fn invalid_info_toml_is_reported() {
+    let dir = std::env::temp_dir().join(format!("rustlings-invalid-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = |info: &str| {
+        std::fs::write(dir.join("info.toml"), info).unwrap();
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("list")
+            .current_dir(&dir)
+            .env_remove("RUSTLINGS_ROOT")
+            .assert()
+    };
+    let unparsable = run("[[exercises]\n");
+    let bad_version = run("min_rustc_version = \"one\"\nexercises = []\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+    unparsable
+        .code(1)
+        .stdout(predicates::str::contains("Failed to read info.toml"));
+    bad_version.code(1).stdout(predicates::str::contains(
+        "min_rustc_version in info.toml isn't a valid version",
+    ));
+}